@@ -0,0 +1,72 @@
+use super::{Api, FindApisError, api_from_spec_document, skip_unparsable};
+use std::path::{Path, PathBuf};
+
+/// Finds the AsyncAPI documents among the files of a repository, either by their conventional
+/// name or by the top level `asyncapi` key.
+pub fn find_apis(files: &[PathBuf]) -> Result<Vec<Api>, FindApisError> {
+    let mut spec_files: Vec<&PathBuf> = files
+        .iter()
+        .filter(|path| is_asyncapi_document(path))
+        .collect();
+    spec_files.sort();
+
+    let mut apis = vec![];
+    for path in spec_files {
+        apis.extend(skip_unparsable(api_from_spec_document(
+            path,
+            "asyncapi",
+            "Event driven API",
+        ))?);
+    }
+
+    Ok(apis)
+}
+
+fn is_asyncapi_document(path: &Path) -> bool {
+    let Some(extension) = path.extension().and_then(|extension| extension.to_str()) else {
+        return false;
+    };
+    if !matches!(extension, "yaml" | "yml" | "json") {
+        return false;
+    }
+
+    if path
+        .file_stem()
+        .is_some_and(|stem| stem.eq_ignore_ascii_case("asyncapi"))
+    {
+        return true;
+    }
+
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    text.lines()
+        .any(|line| line.starts_with("asyncapi:") || line.trim_start().starts_with("\"asyncapi\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_apis() -> Result<()> {
+        let test_dir = tempdir()?;
+        let events_path = test_dir.path().join("events.yaml");
+        std::fs::write(
+            &events_path,
+            "asyncapi: 2.6.0\ninfo:\n  title: Shipment Events\n  version: 1.0.0\nchannels: {}\n",
+        )?;
+        let values_path = test_dir.path().join("values.yaml");
+        std::fs::write(&values_path, "replicas: 2\n")?;
+
+        let apis = find_apis(&[events_path, values_path])?;
+
+        assert_eq!(apis.len(), 1);
+        assert_eq!(apis[0].name, "shipment-events");
+        assert_eq!(apis[0].r#type, "asyncapi");
+
+        Ok(())
+    }
+}
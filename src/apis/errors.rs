@@ -0,0 +1,26 @@
+use crate::git::FindDirectoriesError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FindApisError {
+    #[error("Failed to read the API file {path}: {source}")]
+    FailedToReadApiFile {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the API file {path}: {source}")]
+    FailedToParseApiFile {
+        path: String,
+        source: serde_yml::Error,
+    },
+
+    #[error("Failed to compile API regex: {source}")]
+    FailedToCompileApiRegex { source: regex::Error },
+
+    #[error("Failed to list the repository's directories: {0}")]
+    FindDirectories(#[from] FindDirectoriesError),
+
+    #[error("Failed to finish looking for API definitions: {source}")]
+    FailedToJoinTask { source: tokio::task::JoinError },
+}
@@ -0,0 +1,108 @@
+use super::{Api, ApiDefinition, FindApisError};
+use crate::backstage::entity_name;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Finds the GraphQL schemas among the files of a repository. The schema files in a directory
+/// make up one API.
+pub fn find_apis(files: &[PathBuf]) -> Result<Vec<Api>, FindApisError> {
+    let mut schemas: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    for path in files.iter().filter(|path| {
+        path.extension()
+            .is_some_and(|extension| extension == "graphql" || extension == "graphqls")
+    }) {
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        schemas.entry(root).or_default().push(path.clone());
+    }
+
+    let mut apis = vec![];
+    for (root, mut schema_files) in schemas {
+        schema_files.sort();
+
+        let name = entity_name(
+            &root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "graphql".to_string()),
+        );
+
+        // a single file can be referenced, several have to be embedded together
+        let definition = if schema_files.len() == 1 {
+            ApiDefinition::File(schema_files[0].clone())
+        } else {
+            let mut schema_texts = vec![];
+            for schema_file in &schema_files {
+                let schema_text = std::fs::read_to_string(schema_file).map_err(|source| {
+                    FindApisError::FailedToReadApiFile {
+                        path: format!("{}", schema_file.display()),
+                        source,
+                    }
+                })?;
+                let relative = schema_file.strip_prefix(&root).unwrap_or(schema_file);
+                schema_texts.push(format!("# file: {}\n{schema_text}", relative.display()));
+            }
+            ApiDefinition::Inline(schema_texts.join("\n"))
+        };
+
+        apis.push(Api {
+            description: format!("GraphQL API for {name}"),
+            name,
+            r#type: "graphql".to_string(),
+            root,
+            definition,
+            depends_on: vec![],
+        });
+    }
+
+    Ok(apis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_apis() -> Result<()> {
+        let test_dir = tempdir()?;
+        let mut files = vec![];
+        for (path, contents) in [
+            ("orders/schema.graphql", "type Order { id: ID! }\n"),
+            (
+                "catalog/schema/products.graphql",
+                "type Product { id: ID! }\n",
+            ),
+            (
+                "catalog/schema/query.graphqls",
+                "type Query { products: [Product] }\n",
+            ),
+            ("catalog/schema/README.md", "# Catalog schema\n"),
+        ] {
+            let path = test_dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap_or(test_dir.path()))?;
+            std::fs::write(&path, contents)?;
+            files.push(path);
+        }
+
+        let apis = find_apis(&files)?;
+        assert_eq!(apis.len(), 2);
+
+        assert_eq!(apis[0].name, "schema");
+        assert_eq!(apis[0].r#type, "graphql");
+        assert_eq!(apis[0].description, "GraphQL API for schema");
+        assert!(matches!(
+            &apis[0].definition,
+            ApiDefinition::Inline(schema)
+                if schema == "# file: products.graphql\ntype Product { id: ID! }\n\n# file: query.graphqls\ntype Query { products: [Product] }\n"
+        ));
+
+        assert_eq!(apis[1].name, "orders");
+        assert!(matches!(
+            &apis[1].definition,
+            ApiDefinition::File(path) if *path == files[0]
+        ));
+
+        Ok(())
+    }
+}
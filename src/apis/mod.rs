@@ -0,0 +1,253 @@
+pub mod asyncapi;
+mod errors;
+pub mod graphql;
+pub mod openapi;
+pub mod protobuf;
+
+use crate::backstage::entity_name;
+use crate::config::InternalPatterns;
+pub use errors::FindApisError;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// An API definition found in the repository, which becomes a Backstage `API` entity.
+#[derive(Debug, Clone)]
+pub struct Api {
+    /// The name of the API entity
+    pub name: String,
+
+    /// The description of the API entity
+    pub description: String,
+
+    /// The Backstage API type, e.g. `grpc`
+    pub r#type: String,
+
+    /// The directory the API definition lives in
+    pub root: PathBuf,
+
+    /// The API definition
+    pub definition: ApiDefinition,
+
+    /// Entity refs of the APIs this API depends on
+    pub depends_on: Vec<String>,
+}
+
+/// Where the definition of an API comes from.
+#[derive(Debug, Clone)]
+pub enum ApiDefinition {
+    /// A single file that the catalog can reference with `$text`
+    File(PathBuf),
+
+    /// A definition embedded directly in the entity
+    Inline(String),
+}
+
+/// Finds every API definition among the files of the repository at `repo_root`, which are
+/// expected to leave out installed dependencies and build output. APIs that would share a name
+/// are told apart by the directory they are in.
+pub fn find_apis(
+    repo_root: &Path,
+    files: &[PathBuf],
+    internal: &InternalPatterns,
+) -> Result<Vec<Api>, FindApisError> {
+    let mut apis = protobuf::find_apis(files, internal)?;
+    apis.extend(openapi::find_apis(files)?);
+    apis.extend(asyncapi::find_apis(files)?);
+    apis.extend(graphql::find_apis(files)?);
+
+    disambiguate_names(&mut apis, repo_root);
+
+    Ok(apis)
+}
+
+/// Renames the APIs that share a name, first adding the directory they are in relative to
+/// `repo_root`, then their type and finally a number.
+fn disambiguate_names(apis: &mut [Api], repo_root: &Path) {
+    let suffixes: [fn(&Api, &Path) -> String; 2] = [
+        |api, repo_root| {
+            entity_name(
+                &api.root
+                    .strip_prefix(repo_root)
+                    .unwrap_or(&api.root)
+                    .to_string_lossy(),
+            )
+        },
+        |api, _| api.r#type.clone(),
+    ];
+    for suffix in suffixes {
+        let clashing = clashing_names(apis);
+        for api in apis.iter_mut().filter(|api| clashing.contains(&api.name)) {
+            let suffix = suffix(api, repo_root);
+            if !suffix.is_empty() {
+                api.name = entity_name(&format!("{}-{suffix}", api.name));
+            }
+        }
+    }
+
+    let clashing = clashing_names(apis);
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for api in apis.iter_mut().filter(|api| clashing.contains(&api.name)) {
+        let count = counts.entry(api.name.clone()).or_default();
+        *count += 1;
+        if *count > 1 {
+            api.name = format!("{}-{count}", api.name);
+        }
+    }
+}
+
+/// The names given to more than one API.
+fn clashing_names(apis: &[Api]) -> BTreeSet<String> {
+    let mut seen = BTreeSet::new();
+    apis.iter()
+        .filter(|api| !seen.insert(api.name.as_str()))
+        .map(|api| api.name.clone())
+        .collect()
+}
+
+/// Skips an API definition that can't be parsed with a warning, so one broken file doesn't
+/// stop the rest of the repository being catalogued.
+fn skip_unparsable<T>(result: Result<T, FindApisError>) -> Result<Option<T>, FindApisError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(error @ FindApisError::FailedToParseApiFile { .. }) => {
+            eprintln!("warning: {error}, skipping it");
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// A spec document with an `info` block, as used by OpenAPI, Swagger and AsyncAPI.
+#[derive(Deserialize, Debug)]
+struct SpecDocument {
+    info: Option<SpecInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SpecInfo {
+    title: Option<String>,
+    description: Option<String>,
+}
+
+/// Reads a spec document and builds an API from its `info` block, falling back to the name of
+/// the directory the spec is in.
+fn api_from_spec_document(
+    path: &Path,
+    r#type: &str,
+    type_description: &str,
+) -> Result<Api, FindApisError> {
+    let text =
+        std::fs::read_to_string(path).map_err(|source| FindApisError::FailedToReadApiFile {
+            path: format!("{}", path.display()),
+            source,
+        })?;
+
+    // JSON documents are valid YAML so one parser covers both
+    let document: SpecDocument =
+        serde_yml::from_str(&text).map_err(|source| FindApisError::FailedToParseApiFile {
+            path: format!("{}", path.display()),
+            source,
+        })?;
+
+    let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let info = document.info;
+    let title = info.as_ref().and_then(|info| info.title.clone());
+
+    let name = title
+        .as_deref()
+        .map(entity_name)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| {
+            entity_name(
+                &root
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| r#type.to_string()),
+            )
+        });
+
+    let description = info
+        .and_then(|info| info.description)
+        .map(|description| description.trim().to_string())
+        .filter(|description| !description.is_empty())
+        .unwrap_or_else(|| match &title {
+            Some(title) => format!("{type_description} for {title}"),
+            None => format!("{type_description} for {name}"),
+        });
+
+    Ok(Api {
+        name,
+        description,
+        r#type: r#type.to_string(),
+        root,
+        definition: ApiDefinition::File(path.to_path_buf()),
+        depends_on: vec![],
+    })
+}
+
+/// Returns the path of `path` relative to `base`, written the way Backstage expects relative
+/// locations, e.g. `./proto/payments.proto`.
+pub fn relative_location(base: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(base).unwrap_or(path);
+    if relative.as_os_str().is_empty() {
+        "./".to_string()
+    } else {
+        format!("./{}", relative.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_apis() -> Result<()> {
+        let test_dir = tempdir()?;
+        let mut files = vec![];
+        for (path, contents) in [
+            (
+                "openapi.yaml",
+                "openapi: 3.0.3\ninfo:\n  title: Orders\npaths: {}\n",
+            ),
+            (
+                "asyncapi.yaml",
+                "asyncapi: 2.6.0\ninfo:\n  title: Orders\nchannels: {}\n",
+            ),
+            (
+                "services/legacy/openapi.yaml",
+                "openapi: 3.0.3\ninfo:\n  title: Orders\npaths: {}\n",
+            ),
+            ("broken/swagger.yaml", "info: [unclosed\n"),
+        ] {
+            let path = test_dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap_or(test_dir.path()))?;
+            std::fs::write(&path, contents)?;
+            files.push(path);
+        }
+
+        let mut names: Vec<String> = find_apis(
+            test_dir.path(),
+            &files,
+            &InternalConfig::default().compile()?,
+        )?
+        .into_iter()
+        .map(|api| api.name)
+        .collect();
+        names.sort();
+
+        assert_eq!(
+            names,
+            vec![
+                "orders-asyncapi",
+                "orders-openapi",
+                "orders-services-legacy"
+            ]
+        );
+
+        Ok(())
+    }
+}
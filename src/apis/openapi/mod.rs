@@ -0,0 +1,71 @@
+use super::{Api, FindApisError, api_from_spec_document, skip_unparsable};
+use std::path::PathBuf;
+
+/// The file names OpenAPI and Swagger documents are conventionally given.
+const SPEC_FILE_NAMES: [&str; 6] = [
+    "openapi.yaml",
+    "openapi.yml",
+    "openapi.json",
+    "swagger.yaml",
+    "swagger.yml",
+    "swagger.json",
+];
+
+/// Finds the OpenAPI and Swagger documents among the files of a repository.
+pub fn find_apis(files: &[PathBuf]) -> Result<Vec<Api>, FindApisError> {
+    let mut spec_files: Vec<&PathBuf> = files
+        .iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| SPEC_FILE_NAMES.contains(&name.to_lowercase().as_str()))
+        })
+        .collect();
+    spec_files.sort();
+
+    let mut apis = vec![];
+    for path in spec_files {
+        apis.extend(skip_unparsable(api_from_spec_document(
+            path, "openapi", "REST API",
+        ))?);
+    }
+
+    Ok(apis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apis::ApiDefinition;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_apis() -> Result<()> {
+        let test_dir = tempdir()?;
+        let openapi_path = test_dir.path().join("openapi.yaml");
+        std::fs::write(
+            &openapi_path,
+            "openapi: 3.0.3\ninfo:\n  title: Orders API\n  description: Place and track orders.\n  version: 2.1.0\npaths: {}\n",
+        )?;
+        let swagger_path = test_dir.path().join("legacy").join("swagger.json");
+        std::fs::create_dir_all(test_dir.path().join("legacy"))?;
+        std::fs::write(
+            &swagger_path,
+            r#"{"swagger": "2.0", "info": {"title": "Legacy Inventory", "version": "1.0"}, "paths": {}}"#,
+        )?;
+
+        let apis = find_apis(&[openapi_path.clone(), swagger_path])?;
+        assert_eq!(apis.len(), 2);
+
+        assert_eq!(apis[0].name, "legacy-inventory");
+        assert_eq!(apis[0].description, "REST API for Legacy Inventory");
+
+        assert_eq!(apis[1].name, "orders-api");
+        assert_eq!(apis[1].description, "Place and track orders.");
+        assert_eq!(apis[1].r#type, "openapi");
+        assert!(matches!(&apis[1].definition, ApiDefinition::File(path) if *path == openapi_path));
+
+        Ok(())
+    }
+}
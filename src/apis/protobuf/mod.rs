@@ -0,0 +1,303 @@
+use super::{Api, ApiDefinition, FindApisError, skip_unparsable};
+use crate::config::InternalPatterns;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The parts of a `buf.yaml` file that we care about, covering both the v1 and v2 layouts.
+#[derive(Deserialize, Debug, Default)]
+struct BufYaml {
+    /// The module name in a v1 config, e.g. `buf.build/acme/payments`
+    name: Option<String>,
+
+    /// The modules in a v2 config
+    #[serde(default)]
+    modules: Vec<BufModule>,
+
+    #[serde(default)]
+    deps: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BufModule {
+    #[serde(default)]
+    path: String,
+    name: Option<String>,
+}
+
+/// The parts of a `buf.work.yaml` file that we care about.
+#[derive(Deserialize, Debug)]
+struct BufWorkYaml {
+    #[serde(default)]
+    directories: Vec<String>,
+}
+
+/// A directory of protobuf files that makes up one API.
+#[derive(Debug, Default)]
+struct ProtoModule {
+    name: Option<String>,
+    deps: Vec<String>,
+}
+
+/// Finds the buf modules and plain `.proto` trees among the files of a repository. Only the
+/// dependencies on buf modules of our own organisations are kept.
+pub fn find_apis(
+    files: &[PathBuf],
+    internal: &InternalPatterns,
+) -> Result<Vec<Api>, FindApisError> {
+    let package_regex = Regex::new(r#"(?m)^\s*package\s+([\w.]+)\s*;"#)
+        .map_err(|source| FindApisError::FailedToCompileApiRegex { source })?;
+
+    let mut modules: BTreeMap<PathBuf, ProtoModule> = BTreeMap::new();
+
+    // every directory listed in a buf workspace is a module
+    for path in files
+        .iter()
+        .filter(|path| has_file_name(path, "buf.work.yaml"))
+    {
+        let Some(buf_work) = skip_unparsable(read_yaml::<BufWorkYaml>(path))? else {
+            continue;
+        };
+        let workspace_root = path.parent().unwrap_or(Path::new(""));
+        for directory in buf_work.directories {
+            modules.entry(workspace_root.join(directory)).or_default();
+        }
+    }
+
+    for path in files.iter().filter(|path| has_file_name(path, "buf.yaml")) {
+        let Some(buf_yaml) = skip_unparsable(read_yaml::<BufYaml>(path))? else {
+            continue;
+        };
+        let config_root = path.parent().unwrap_or(Path::new(""));
+
+        let deps: Vec<String> = buf_yaml
+            .deps
+            .iter()
+            .filter_map(|dep| internal.buf_module_name(dep))
+            .map(|module| format!("api:{}", module_name(module)))
+            .collect();
+
+        if buf_yaml.modules.is_empty() {
+            let module = modules.entry(config_root.to_path_buf()).or_default();
+            module.name = buf_yaml.name.as_deref().map(module_name);
+            module.deps = deps;
+        } else {
+            for buf_module in buf_yaml.modules {
+                let module = modules
+                    .entry(config_root.join(&buf_module.path))
+                    .or_default();
+                module.name = buf_module.name.as_deref().map(module_name);
+                module.deps = deps.clone();
+            }
+        }
+    }
+
+    let mut proto_files: Vec<&PathBuf> = files
+        .iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "proto")
+        })
+        .collect();
+    proto_files.sort_by_key(|path| (path.components().count(), path.to_path_buf()));
+
+    // group the proto files under their module, or under the top-most directory of a plain
+    // proto tree
+    let mut trees: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    for proto_file in proto_files {
+        let tree_root = modules
+            .keys()
+            .chain(trees.keys())
+            .filter(|root| proto_file.starts_with(root))
+            .min_by_key(|root| root.components().count())
+            .cloned()
+            .unwrap_or_else(|| proto_file.parent().unwrap_or(Path::new("")).to_path_buf());
+
+        trees.entry(tree_root).or_default().push(proto_file.clone());
+    }
+
+    let mut apis = vec![];
+    for (root, proto_files) in trees {
+        let module = modules.remove(&root).unwrap_or_default();
+
+        let mut proto_texts = vec![];
+        for proto_file in &proto_files {
+            let proto_text = std::fs::read_to_string(proto_file).map_err(|source| {
+                FindApisError::FailedToReadApiFile {
+                    path: format!("{}", proto_file.display()),
+                    source,
+                }
+            })?;
+            proto_texts.push(proto_text);
+        }
+
+        let package = proto_texts
+            .iter()
+            .find_map(|proto_text| package_regex.captures(proto_text))
+            .and_then(|captures| captures.get(1))
+            .map(|package| package.as_str().to_string());
+
+        let name = module
+            .name
+            .clone()
+            .or_else(|| package.as_ref().map(|package| package.replace('.', "-")))
+            .or_else(|| {
+                root.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| "protobuf".to_string());
+
+        let description = match &package {
+            Some(package) => format!("gRPC API defined by the {package} protobuf package"),
+            None => format!("gRPC API defined by the {name} protobuf files"),
+        };
+
+        // a single file can be referenced, several have to be embedded together
+        let definition = if proto_files.len() == 1 {
+            ApiDefinition::File(proto_files[0].clone())
+        } else {
+            ApiDefinition::Inline(
+                proto_files
+                    .iter()
+                    .zip(proto_texts)
+                    .map(|(proto_file, proto_text)| {
+                        let relative = proto_file.strip_prefix(&root).unwrap_or(proto_file);
+                        format!("// file: {}\n{proto_text}", relative.display())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        };
+
+        apis.push(Api {
+            name,
+            description,
+            r#type: "grpc".to_string(),
+            root,
+            definition,
+            depends_on: module.deps,
+        });
+    }
+
+    Ok(apis)
+}
+
+fn has_file_name(path: &Path, file_name: &str) -> bool {
+    path.file_name().is_some_and(|name| name == file_name)
+}
+
+/// Turns a buf module name like `buf.build/acme/payments` into an entity name.
+fn module_name(module: &str) -> String {
+    module.rsplit('/').next().unwrap_or(module).to_string()
+}
+
+fn read_yaml<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, FindApisError> {
+    let text =
+        std::fs::read_to_string(path).map_err(|source| FindApisError::FailedToReadApiFile {
+            path: format!("{}", path.display()),
+            source,
+        })?;
+
+    serde_yml::from_str(&text).map_err(|source| FindApisError::FailedToParseApiFile {
+        path: format!("{}", path.display()),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    fn write(root: &Path, path: &str, contents: &str) -> Result<PathBuf> {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap_or(root))?;
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    }
+
+    #[test]
+    fn test_find_apis() -> Result<()> {
+        let test_dir = tempdir()?;
+        let root = test_dir.path();
+
+        let files = vec![
+            write(
+                root,
+                "buf.work.yaml",
+                "version: v1\ndirectories:\n  - proto\n",
+            )?,
+            write(
+                root,
+                "proto/buf.yaml",
+                "version: v1\nname: buf.build/bxbdigital/payments\ndeps:\n  - buf.build/bxbdigital/common\n  - buf.build/googleapis/googleapis\n",
+            )?,
+            write(
+                root,
+                "proto/payments/v1/payments.proto",
+                "syntax = \"proto3\";\npackage payments.v1;\n",
+            )?,
+            write(
+                root,
+                "proto/payments/v1/refunds.proto",
+                "syntax = \"proto3\";\npackage payments.v1;\n",
+            )?,
+            write(
+                root,
+                "legacy/events/events.proto",
+                "syntax = \"proto3\";\npackage acme.events;\n",
+            )?,
+        ];
+
+        let apis = find_apis(&files, &InternalConfig::default().compile()?)?;
+        assert_eq!(apis.len(), 2);
+
+        let events = &apis[0];
+        assert_eq!(events.name, "acme-events");
+        assert!(
+            matches!(&events.definition, ApiDefinition::File(path) if path.ends_with("events.proto"))
+        );
+
+        let payments = &apis[1];
+        assert_eq!(payments.name, "payments");
+        assert_eq!(payments.r#type, "grpc");
+        assert_eq!(payments.depends_on, vec!["api:common"]);
+        assert!(
+            matches!(&payments.definition, ApiDefinition::Inline(text) if text.contains("// file: payments/v1/refunds.proto"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_apis_configured_orgs() -> Result<()> {
+        let test_dir = tempdir()?;
+        let files = vec![
+            write(
+                test_dir.path(),
+                "buf.yaml",
+                "version: v2\nmodules:\n  - path: proto\n    name: buf.acme.dev/acme/billing\ndeps:\n  - buf.acme.dev/acme/money:v1.0.0\n  - buf.build/acme/common\n  - buf.build/bxbdigital/common\n",
+            )?,
+            write(
+                test_dir.path(),
+                "proto/billing/v1/billing.proto",
+                "syntax = \"proto3\";\npackage billing.v1;\n",
+            )?,
+        ];
+        let internal = InternalConfig {
+            hosts: vec!["github.com".to_string()],
+            orgs: vec!["acme".to_string()],
+            domains: vec!["acme.dev".to_string()],
+        }
+        .compile()?;
+
+        let apis = find_apis(&files, &internal)?;
+        assert_eq!(apis.len(), 1);
+        assert_eq!(apis[0].depends_on, vec!["api:money", "api:common"]);
+
+        Ok(())
+    }
+}
@@ -1,4 +1,10 @@
+use crate::codeowners::LoadCodeOwnersError;
+use crate::docker::AnalyseDockerfilesError;
 use crate::git::{GetOriginRemoteRepoNameError, GitRepoTryFromError};
+use crate::languages::errors::{GetAnnotationsError, GetMetadataError};
+use crate::ownership::SuggestOwnerError;
+use crate::project::GetProjectDependenciesError;
+use crate::readme::ReadReadmeError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -39,3 +45,30 @@ pub enum NewCatalogInfoError {
     #[error("Failed to get the repo name due to error: {0}")]
     FailedToGetOriginRemoteRepoName(#[from] GetOriginRemoteRepoNameError),
 }
+
+#[derive(Error, Debug)]
+pub enum ProjectCatalogInfoError {
+    #[error("Failed to get the project's internal dependencies: {0}")]
+    GetInternalDependencies(#[from] GetProjectDependenciesError),
+
+    #[error("Failed to get the project's annotations: {0}")]
+    GetAnnotations(#[from] GetAnnotationsError),
+
+    #[error("Failed to get the project's metadata: {0}")]
+    GetMetadata(#[from] GetMetadataError),
+
+    #[error("Failed to analyse the project's Dockerfiles: {0}")]
+    AnalyseDockerfiles(#[from] AnalyseDockerfilesError),
+
+    #[error("Failed to compile the internal repository and package patterns: {0}")]
+    CompileInternalPatterns(#[from] regex::Error),
+
+    #[error("Failed to load the repository's CODEOWNERS file: {0}")]
+    LoadCodeOwners(#[from] LoadCodeOwnersError),
+
+    #[error("Failed to suggest an owner from the git history: {0}")]
+    SuggestOwner(#[from] SuggestOwnerError),
+
+    #[error("Failed to read the project's README: {0}")]
+    ReadReadme(#[from] ReadReadmeError),
+}
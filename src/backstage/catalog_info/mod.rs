@@ -1,13 +1,34 @@
 mod errors;
 
-use crate::git::GitRepo;
+use crate::apis::{Api, ApiDefinition, relative_location};
+use crate::codeowners::CodeOwners;
+use crate::config::Config;
+use crate::docker::DockerfileAnalysis;
+use crate::git::{GitRemote, GitRepo};
+use crate::index::{DependencyIndex, PACKAGES_ANNOTATION};
+use crate::ownership::suggest_owner;
+use crate::project::{InternalDependency, Project};
+use crate::readme::Readme;
+use crate::techdocs::techdocs_ref;
 pub use errors::{
     CatalogInfoExistsError, CatalogInfoParseError, GetCatalogInfoError, NewCatalogInfoError,
+    ProjectCatalogInfoError,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::path::Path;
 
-const CATALOG_INFO_FILE: &str = "catalog-info.yaml";
+/// The name Backstage gives to catalog info files.
+pub const CATALOG_INFO_FILE: &str = "catalog-info.yaml";
+
+/// The annotation on every entity we generate, which tells the catalog info files we wrote
+/// apart from hand-written ones.
+pub const GENERATED_BY_ANNOTATION: &str = "metaforge.io/generated-by";
+
+/// The annotations every generated entity starts with.
+pub fn generated_annotations() -> BTreeMap<String, String> {
+    BTreeMap::from([(GENERATED_BY_ANNOTATION.to_string(), "metaforge".to_string())])
+}
 
 /// A struct that describes a Backstage catalog info file.
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -39,7 +60,8 @@ pub struct CatalogInfoMetadata {
     pub annotations: BTreeMap<String, String>,
 
     /// The tags of the entity
-    pub tags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 
     /// The links for the entity
     pub links: Vec<CatalogInfoMetadataLink>,
@@ -71,11 +93,38 @@ pub struct CatalogInfoSpec {
     pub r#type: String,
 
     /// The system that the entity belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub system: Option<String>,
 
     /// The dependencies of the entity
     #[serde(rename = "dependsOn")]
     pub depends_on: Vec<String>,
+
+    /// The APIs provided by the entity
+    #[serde(
+        rename = "providesApis",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub provides_apis: Vec<String>,
+
+    /// The definition of an API entity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<CatalogInfoDefinition>,
+}
+
+/// The definition of an API entity, either embedded or substituted from a file by Backstage.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum CatalogInfoDefinition {
+    /// A definition read from a file relative to the catalog-info file
+    Text {
+        #[serde(rename = "$text")]
+        text: String,
+    },
+
+    /// A definition embedded in the catalog-info file
+    Inline(String),
 }
 
 impl CatalogInfo {
@@ -106,7 +155,7 @@ impl CatalogInfo {
                     "A Backstage catalog info file for the {repo_name} repository"
                 ),
                 annotations: BTreeMap::new(),
-                tags: BTreeMap::new(),
+                tags: vec![],
                 links: vec![],
             },
             spec: CatalogInfoSpec {
@@ -115,10 +164,224 @@ impl CatalogInfo {
                 r#type: "service".to_string(),
                 system: Some("a_system".to_string()),
                 depends_on: vec![],
+                provides_apis: vec![],
+                definition: None,
+            },
+        })
+    }
+
+    /// Creates the catalog info for a project found in the repository, using `repo_name` for the
+    /// project that sits at the repository root and `index` to resolve its dependencies.
+    pub fn for_project(
+        repo: &GitRepo,
+        repo_name: &str,
+        project: &Project,
+        config: &Config,
+        index: &DependencyIndex,
+    ) -> Result<CatalogInfo, ProjectCatalogInfoError> {
+        let name = project.get_name(&repo.root, repo_name);
+        let self_ref = project.get_entity_ref(&repo.root, repo_name);
+        let metadata = project.get_metadata()?;
+        let readme = Readme::read(&project.root)?.unwrap_or_default();
+        let component_type = project.infer_component_type(&name, &config.component_types);
+        let relative_root = project
+            .root
+            .strip_prefix(&repo.root)
+            .unwrap_or(Path::new(""));
+        let codeowners = CodeOwners::load(&repo.root)?;
+        let owner = codeowners.owner_of(relative_root, &config.owners);
+
+        let mut annotations = generated_annotations();
+        for language in &project.languages {
+            annotations.extend(language.get_annotations(&project.root)?);
+        }
+        annotations.extend(
+            DockerfileAnalysis::analyse(
+                &project.root,
+                &config.docker,
+                &config.internal.compile()?,
+            )?
+            .get_annotations(),
+        );
+        annotations.insert(
+            "metaforge.io/type-rule".to_string(),
+            component_type.rule.clone(),
+        );
+        let catalog_dir = config.layout.catalog_dir(&repo.root, &project.root);
+        if let Some(remote) = repo.get_origin_remote() {
+            let catalog_path = catalog_dir.join(CATALOG_INFO_FILE);
+            annotations.extend(
+                remote.annotations(
+                    &repo.get_default_branch(),
+                    relative_root,
+                    catalog_path
+                        .strip_prefix(&repo.root)
+                        .unwrap_or(&catalog_path),
+                ),
+            );
+        }
+        if let Some(techdocs_ref) = techdocs_ref(&project.root, &catalog_dir) {
+            annotations.insert("backstage.io/techdocs-ref".to_string(), techdocs_ref);
+        }
+        // without a CODEOWNERS file the owner is only suggested, for a person to confirm
+        if codeowners.rules.is_empty()
+            && let Some(suggestion) = suggest_owner(&repo.root, relative_root, &config.owners)?
+        {
+            annotations.insert("metaforge.io/suggested-owner".to_string(), suggestion.owner);
+            annotations.insert(
+                "metaforge.io/suggested-owner-confidence".to_string(),
+                format!("{:.2}", suggestion.confidence),
+            );
+        }
+        let package_names = project.get_package_names()?;
+        if !package_names.is_empty() {
+            annotations.insert(PACKAGES_ANNOTATION.to_string(), package_names.join(", "));
+        }
+        if let Some(license) = &metadata.license {
+            annotations.insert("metaforge.io/license".to_string(), license.clone());
+        }
+        if let Some(version) = &metadata.version {
+            annotations.insert("metaforge.io/version".to_string(), version.clone());
+        }
+        if !metadata.authors.is_empty() {
+            annotations.insert(
+                "metaforge.io/authors".to_string(),
+                metadata.authors.join(", "),
+            );
+        }
+
+        let mut tags = project.get_language_tags();
+        for keyword in &metadata.keywords {
+            let tag = tag_name(keyword);
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        // repositories on hosts we don't know get no icon
+        let repository_icon = metadata
+            .repository
+            .as_deref()
+            .and_then(GitRemote::parse)
+            .and_then(|remote| remote.git_host())
+            .map(|host| host.icon());
+        let mut links: Vec<CatalogInfoMetadataLink> = [
+            ("Homepage", Some("web"), &metadata.homepage),
+            ("Repository", repository_icon, &metadata.repository),
+            ("Documentation", Some("docs"), &metadata.documentation),
+        ]
+        .into_iter()
+        .filter_map(|(title, icon, url)| {
+            url.as_ref().map(|url| CatalogInfoMetadataLink {
+                icon: icon.map(|icon| icon.to_string()),
+                title: title.to_string(),
+                url: url.clone(),
+            })
+        })
+        .collect();
+        // the README's links are only used when the manifests don't provide any
+        if links.is_empty() {
+            links = readme
+                .links
+                .into_iter()
+                .map(|link| CatalogInfoMetadataLink {
+                    icon: None,
+                    title: link.title,
+                    url: link.url,
+                })
+                .collect();
+        }
+
+        let description = metadata
+            .description
+            .clone()
+            .or(readme.description)
+            .unwrap_or_else(|| format!("A Backstage catalog info file for the {name} project"));
+
+        let dependencies = project.get_internal_dependencies(&self_ref, config, index)?;
+        if config.dependencies.annotate_versions {
+            annotations.extend(dependency_annotations(&dependencies));
+        }
+        // indirect dependencies are recorded separately, as they aren't the project's choice
+        let (indirect, direct): (Vec<InternalDependency>, Vec<InternalDependency>) = dependencies
+            .into_iter()
+            .partition(|dependency| dependency.indirect);
+        if !indirect.is_empty() {
+            let indirect: Vec<String> = indirect
+                .into_iter()
+                .map(|dependency| dependency.entity_ref)
+                .collect();
+            annotations.insert(
+                "metaforge.io/indirect-dependencies".to_string(),
+                indirect.join(", "),
+            );
+        }
+        let depends_on = direct
+            .into_iter()
+            .map(|dependency| dependency.entity_ref)
+            .collect();
+
+        let provides_apis = repo
+            .get_project_apis(project)
+            .iter()
+            .map(|api| format!("api:{}", api.name))
+            .collect();
+
+        Ok(CatalogInfo {
+            api_version: "backstage.io/v1alpha1".to_string(),
+            kind: "Component".to_string(),
+            metadata: CatalogInfoMetadata {
+                name,
+                description,
+                annotations,
+                tags,
+                links,
+            },
+            spec: CatalogInfoSpec {
+                lifecycle: "experimental".to_string(),
+                owner,
+                r#type: component_type.r#type,
+                system: config.system.system_name(repo_name),
+                depends_on,
+                provides_apis,
+                definition: None,
             },
         })
     }
 
+    /// Creates the catalog info for an API provided by a component, which shares the
+    /// component's owner, lifecycle and system. File definitions are referenced relative to
+    /// `catalog_dir`, the directory the catalog info file is written to.
+    pub fn for_api(api: &Api, catalog_dir: &Path, component: &CatalogInfo) -> CatalogInfo {
+        let definition = match &api.definition {
+            ApiDefinition::File(path) => CatalogInfoDefinition::Text {
+                text: relative_location(catalog_dir, path),
+            },
+            ApiDefinition::Inline(text) => CatalogInfoDefinition::Inline(text.clone()),
+        };
+
+        CatalogInfo {
+            api_version: "backstage.io/v1alpha1".to_string(),
+            kind: "API".to_string(),
+            metadata: CatalogInfoMetadata {
+                name: api.name.clone(),
+                description: api.description.clone(),
+                annotations: generated_annotations(),
+                tags: vec![],
+                links: vec![],
+            },
+            spec: CatalogInfoSpec {
+                lifecycle: component.spec.lifecycle.clone(),
+                owner: component.spec.owner.clone(),
+                r#type: api.r#type.clone(),
+                system: component.spec.system.clone(),
+                depends_on: api.depends_on.clone(),
+                provides_apis: vec![],
+                definition: Some(definition),
+            },
+        }
+    }
+
     /// Checks if the Backstage catalog info file exists in the repository root.
     pub async fn exists() -> Result<bool, CatalogInfoExistsError> {
         // find the root of the repository
@@ -135,3 +398,204 @@ impl CatalogInfo {
         Ok(serde_yml::from_reader(catalog_info_file)?)
     }
 }
+
+/// Annotates each internal dependency's versions and sources, under
+/// `dependency-version.metaforge.io/<name>` and `dependency-source.metaforge.io/<name>` where
+/// `<name>` is the name of the component depended on, and the versions and commits built under
+/// `dependency-resolved.metaforge.io/<name>` and `dependency-commit.metaforge.io/<name>`.
+fn dependency_annotations(dependencies: &[InternalDependency]) -> BTreeMap<String, String> {
+    let mut annotations = BTreeMap::new();
+    for dependency in dependencies {
+        let name = dependency
+            .entity_ref
+            .rsplit('/')
+            .next()
+            .unwrap_or(&dependency.entity_ref);
+        if !dependency.versions.is_empty() {
+            annotations.insert(
+                format!("dependency-version.metaforge.io/{name}"),
+                dependency.versions.join(", "),
+            );
+        }
+        if !dependency.sources.is_empty() {
+            let sources: Vec<String> = dependency
+                .sources
+                .iter()
+                .map(|source| source.to_string())
+                .collect();
+            annotations.insert(
+                format!("dependency-source.metaforge.io/{name}"),
+                sources.join(", "),
+            );
+        }
+        if !dependency.resolved_versions.is_empty() {
+            annotations.insert(
+                format!("dependency-resolved.metaforge.io/{name}"),
+                dependency.resolved_versions.join(", "),
+            );
+        }
+        if !dependency.commits.is_empty() {
+            annotations.insert(
+                format!("dependency-commit.metaforge.io/{name}"),
+                dependency.commits.join(", "),
+            );
+        }
+    }
+    annotations
+}
+
+/// Turns a keyword into a valid Backstage tag, which may only contain lowercase letters, digits
+/// and `+`, `#` and `-`.
+fn tag_name(keyword: &str) -> String {
+    keyword
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '+' | '#' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .take(63)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_repo;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    /// Runs git in `dir` as Alice.
+    fn git(dir: &Path, args: &[&str]) -> Result<()> {
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=Alice",
+                "-c",
+                "user.email=alice@bxbdigital.com",
+            ])
+            .args(args)
+            .output()?;
+        Ok(())
+    }
+
+    /// Creates a git repository holding a JavaScript project that provides an OpenAPI API.
+    fn payments_repo(dir: &Path) -> Result<()> {
+        git(dir, &["init"])?;
+        git(
+            dir,
+            &[
+                "remote",
+                "add",
+                "origin",
+                "git@bitbucket.org:bxbdigital/payments.git",
+            ],
+        )?;
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{
+  "name": "payments",
+  "description": "Takes payments",
+  "homepage": "https://payments.example.com",
+  "repository": { "type": "git", "url": "https://bitbucket.org/bxbdigital/payments" }
+}"#,
+        )?;
+        std::fs::write(
+            dir.join("openapi.yaml"),
+            "openapi: 3.0.3\ninfo:\n  title: Payments API\n  version: 1.0.0\npaths: {}\n",
+        )?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_project() -> Result<()> {
+        let test_dir = tempdir()?;
+        payments_repo(test_dir.path())?;
+        std::fs::write(
+            test_dir.path().join("CODEOWNERS"),
+            "* @bxbdigital/payments-team\n",
+        )?;
+
+        let scanned = scan_repo(Some(test_dir.path().into())).await?;
+        let catalog_info = CatalogInfo::for_project(
+            &scanned.repo,
+            &scanned.name,
+            &scanned.repo.projects[0],
+            &scanned.config,
+            &DependencyIndex::default(),
+        )?;
+
+        assert_eq!(catalog_info.metadata.name, "payments");
+        assert_eq!(catalog_info.metadata.description, "Takes payments");
+        assert_eq!(catalog_info.spec.owner, "group:default/payments-team");
+        assert!(
+            !catalog_info
+                .metadata
+                .annotations
+                .contains_key("metaforge.io/suggested-owner")
+        );
+        assert_eq!(catalog_info.spec.provides_apis, vec!["api:payments-api"]);
+
+        let links: Vec<(&str, Option<&str>, &str)> = catalog_info
+            .metadata
+            .links
+            .iter()
+            .map(|link| (link.title.as_str(), link.icon.as_deref(), link.url.as_str()))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("Homepage", Some("web"), "https://payments.example.com"),
+                (
+                    "Repository",
+                    Some("bitbucket"),
+                    "https://bitbucket.org/bxbdigital/payments"
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_project_suggested_owner() -> Result<()> {
+        let test_dir = tempdir()?;
+        payments_repo(test_dir.path())?;
+        git(test_dir.path(), &["add", "-A"])?;
+        git(test_dir.path(), &["commit", "-m", "Take payments"])?;
+
+        let scanned = scan_repo(Some(test_dir.path().into())).await?;
+        let catalog_info = CatalogInfo::for_project(
+            &scanned.repo,
+            &scanned.name,
+            &scanned.repo.projects[0],
+            &scanned.config,
+            &DependencyIndex::default(),
+        )?;
+
+        // without a CODEOWNERS file the owner falls back to the default, with a suggestion
+        assert_eq!(catalog_info.spec.owner, scanned.config.owners.default);
+        assert_eq!(
+            catalog_info
+                .metadata
+                .annotations
+                .get("metaforge.io/suggested-owner")
+                .map(|owner| owner.as_str()),
+            Some("user:default/alice")
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,204 @@
+use std::path::{Path, PathBuf};
+
 // A backstage component object
+#[derive(Debug, Clone)]
 pub struct Component {
     pub name: String,
+
+    /// Where the dependency on the component was declared
+    pub location: Option<SourceLocation>,
+
+    /// The version of the component required, e.g. `v1.4.0` or `^2.1.0`
+    pub version: Option<String>,
+
+    /// Where the component is fetched from
+    pub source: Option<DependencySource>,
+
+    /// The exact version built, from the project's lockfile
+    pub resolved_version: Option<String>,
+
+    /// The commit built, for components fetched from git
+    pub commit: Option<String>,
+
+    /// Whether the component is only depended on through other dependencies
+    pub indirect: bool,
+}
+
+/// Where a dependency is fetched from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DependencySource {
+    /// A package registry, named when it isn't the language's default one
+    Registry { name: Option<String> },
+
+    /// A git repository, at a specific revision, tag or branch if one is given
+    Git {
+        url: String,
+        reference: Option<GitReference>,
+    },
+
+    /// A directory on disk, relative to the project
+    Path { path: String },
+}
+
+/// The commit of a git dependency.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GitReference {
+    Rev(String),
+    Tag(String),
+    Branch(String),
+}
+
+/// A line in a project file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceLocation {
+    pub file: PathBuf,
+
+    /// The line number, starting from 1
+    pub line: usize,
+}
+
+impl Component {
+    /// Creates a component that was referenced at `location`.
+    pub fn at(name: &str, location: Option<SourceLocation>) -> Component {
+        Component {
+            name: name.to_string(),
+            location,
+            version: None,
+            source: None,
+            resolved_version: None,
+            commit: None,
+            indirect: false,
+        }
+    }
+
+    /// Records the version of the component that is required.
+    pub fn with_version(mut self, version: &str) -> Component {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Marks the component as only depended on through other dependencies.
+    pub fn as_indirect(mut self) -> Component {
+        self.indirect = true;
+        self
+    }
+
+    /// Records where the component is fetched from.
+    pub fn with_source(mut self, source: DependencySource) -> Component {
+        self.source = Some(source);
+        self
+    }
+
+    /// Returns the Backstage entity ref of the component, e.g. `component:default/payments`.
+    ///
+    /// Module paths are reduced to their last segment, skipping Go major version suffixes, and
+    /// the name is made a valid entity name.
+    pub fn entity_ref(&self) -> String {
+        let name = self
+            .name
+            .trim_end_matches('/')
+            .rsplit('/')
+            .find(|segment| !is_major_version_suffix(segment))
+            .unwrap_or(&self.name);
+
+        format!("component:default/{}", entity_name(name))
+    }
+}
+
+impl SourceLocation {
+    /// The location of the byte `offset` within the `text` of `file`.
+    pub fn from_offset(file: &Path, text: &str, offset: usize) -> SourceLocation {
+        SourceLocation {
+            file: file.to_path_buf(),
+            line: text[..offset.min(text.len())].matches('\n').count() + 1,
+        }
+    }
+
+    /// The location of the first line in the `text` of `file` that contains `needle`.
+    pub fn find(file: &Path, text: &str, needle: &str) -> Option<SourceLocation> {
+        text.find(needle)
+            .map(|offset| SourceLocation::from_offset(file, text, offset))
+    }
+}
+
+impl GitReference {
+    /// The revision, tag or branch name.
+    pub fn name(&self) -> &str {
+        match self {
+            GitReference::Rev(name) | GitReference::Tag(name) | GitReference::Branch(name) => name,
+        }
+    }
+
+    /// The version the reference pins, which a branch doesn't as it moves.
+    pub fn version(&self) -> Option<&str> {
+        match self {
+            GitReference::Rev(name) | GitReference::Tag(name) => Some(name),
+            GitReference::Branch(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DependencySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DependencySource::Registry { name: None } => write!(f, "registry"),
+            DependencySource::Registry { name: Some(name) } => write!(f, "registry:{name}"),
+            DependencySource::Git {
+                url,
+                reference: None,
+            } => write!(f, "git:{url}"),
+            DependencySource::Git {
+                url,
+                reference: Some(reference),
+            } => write!(f, "git:{url}#{reference}"),
+            DependencySource::Path { path } => write!(f, "path:{path}"),
+        }
+    }
+}
+
+impl std::fmt::Display for GitReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GitReference::Rev(rev) => write!(f, "rev={rev}"),
+            GitReference::Tag(tag) => write!(f, "tag={tag}"),
+            GitReference::Branch(branch) => write!(f, "branch={branch}"),
+        }
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+fn is_major_version_suffix(segment: &str) -> bool {
+    segment
+        .strip_prefix('v')
+        .is_some_and(|version| !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Turns a name like `Orders_API` into a valid entity name like `orders_api`.
+pub fn entity_name(name: &str) -> String {
+    let name = name
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+
+    name.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .take(63)
+        .collect::<String>()
+        .trim_end_matches(['-', '_', '.'])
+        .to_string()
 }
@@ -0,0 +1,48 @@
+use super::generated_annotations;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A Backstage `Location` entity, which points the catalog at other catalog info files.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Location {
+    #[serde(rename = "apiVersion")]
+    pub api_version: String,
+
+    pub kind: String,
+
+    pub metadata: LocationMetadata,
+
+    pub spec: LocationSpec,
+}
+
+/// The metadata of a Location entity.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LocationMetadata {
+    pub name: String,
+    pub description: String,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// The spec of a Location entity.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LocationSpec {
+    /// The catalog info files, relative to the file containing the Location
+    pub targets: Vec<String>,
+}
+
+impl Location {
+    pub fn new(name: &str, targets: Vec<String>) -> Location {
+        Location {
+            api_version: "backstage.io/v1alpha1".to_string(),
+            kind: "Location".to_string(),
+            metadata: LocationMetadata {
+                name: name.to_string(),
+                description: format!("The catalog info files of the projects in {name}"),
+                annotations: generated_annotations(),
+            },
+            spec: LocationSpec { targets },
+        }
+    }
+}
@@ -1,4 +1,11 @@
 mod catalog_info;
 mod component;
-pub use catalog_info::{CatalogInfo, CatalogInfoExistsError};
-pub use component::Component;
+mod location;
+mod system;
+pub use catalog_info::{
+    CATALOG_INFO_FILE, CatalogInfo, CatalogInfoExistsError, GENERATED_BY_ANNOTATION,
+    ProjectCatalogInfoError, generated_annotations,
+};
+pub use component::{Component, DependencySource, GitReference, SourceLocation, entity_name};
+pub use location::{Location, LocationMetadata, LocationSpec};
+pub use system::{Domain, DomainSpec, System, SystemMetadata, SystemSpec};
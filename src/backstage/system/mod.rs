@@ -0,0 +1,188 @@
+use super::{entity_name, generated_annotations};
+use crate::codeowners::{CodeOwners, LoadCodeOwnersError};
+use crate::config::Config;
+use crate::git::GitRepo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A Backstage `System` entity, grouping the components and APIs of a repository.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct System {
+    #[serde(rename = "apiVersion")]
+    pub api_version: String,
+
+    pub kind: String,
+
+    pub metadata: SystemMetadata,
+
+    pub spec: SystemSpec,
+}
+
+/// A Backstage `Domain` entity, grouping systems that belong to the same business area.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Domain {
+    #[serde(rename = "apiVersion")]
+    pub api_version: String,
+
+    pub kind: String,
+
+    pub metadata: SystemMetadata,
+
+    pub spec: DomainSpec,
+}
+
+/// The metadata of a System or Domain entity.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SystemMetadata {
+    pub name: String,
+    pub description: String,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// The spec of a System entity.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SystemSpec {
+    pub owner: String,
+
+    /// The domain the system belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+}
+
+/// The spec of a Domain entity.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DomainSpec {
+    pub owner: String,
+}
+
+impl System {
+    pub fn new(name: &str, description: &str, owner: &str, domain: Option<String>) -> System {
+        System {
+            api_version: "backstage.io/v1alpha1".to_string(),
+            kind: "System".to_string(),
+            metadata: SystemMetadata {
+                name: name.to_string(),
+                description: description.to_string(),
+                annotations: generated_annotations(),
+            },
+            spec: SystemSpec {
+                owner: owner.to_string(),
+                domain,
+            },
+        }
+    }
+
+    /// Creates the System entity for the repository when the config asks for one. The system is
+    /// owned by the configured owner or else whoever owns the repository root.
+    pub fn for_repo(
+        repo: &GitRepo,
+        repo_name: &str,
+        config: &Config,
+    ) -> Result<Option<System>, LoadCodeOwnersError> {
+        let Some(name) = config
+            .system
+            .system_name(repo_name)
+            .filter(|_| config.system.generate)
+        else {
+            return Ok(None);
+        };
+
+        let owner = match &config.system.owner {
+            Some(owner) => owner.clone(),
+            None => CodeOwners::load(&repo.root)?.owner_of(Path::new(""), &config.owners),
+        };
+        let description = config
+            .system
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("The projects in the {repo_name} repository"));
+
+        Ok(Some(System::new(
+            &name,
+            &description,
+            &owner,
+            config.system.domain.as_deref().map(entity_name),
+        )))
+    }
+}
+
+impl Domain {
+    /// Creates the Domain entity of a system when the config asks for one. The description
+    /// keeps the domain's name as configured, e.g. "Finance Ops" rather than `finance-ops`.
+    pub fn for_system(system: &System, config: &Config) -> Option<Domain> {
+        let domain = system
+            .spec
+            .domain
+            .as_ref()
+            .filter(|_| config.system.generate_domain)?;
+        let display_name = config.system.domain.as_deref().unwrap_or(domain);
+
+        Some(Domain::new(
+            &entity_name(domain),
+            &format!("The {display_name} domain"),
+            &system.spec.owner,
+        ))
+    }
+
+    pub fn new(name: &str, description: &str, owner: &str) -> Domain {
+        Domain {
+            api_version: "backstage.io/v1alpha1".to_string(),
+            kind: "Domain".to_string(),
+            metadata: SystemMetadata {
+                name: name.to_string(),
+                description: description.to_string(),
+                annotations: generated_annotations(),
+            },
+            spec: DomainSpec {
+                owner: owner.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SystemConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_for_repo() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+        std::fs::write(
+            test_dir.path().join("CODEOWNERS"),
+            "* @bxbdigital/payments\n",
+        )?;
+        let repo = GitRepo::try_from(Some(test_dir.path().into())).await?;
+
+        let mut config = Config::default();
+        assert_eq!(System::for_repo(&repo, "Payments Platform", &config)?, None);
+
+        config.system = SystemConfig {
+            generate: true,
+            domain: Some("Finance Ops".to_string()),
+            generate_domain: true,
+            ..SystemConfig::default()
+        };
+        let system =
+            System::for_repo(&repo, "Payments Platform", &config)?.expect("a system to be made");
+
+        assert_eq!(system.metadata.name, "payments-platform");
+        assert_eq!(system.spec.owner, "group:default/payments");
+        assert_eq!(
+            config.system.system_name("Payments Platform"),
+            Some(system.metadata.name.clone())
+        );
+        assert_eq!(system.spec.domain.as_deref(), Some("finance-ops"));
+        let domain = Domain::for_system(&system, &config).expect("a domain to be made");
+        assert_eq!(domain.metadata.name, "finance-ops");
+        assert_eq!(domain.metadata.description, "The Finance Ops domain");
+
+        Ok(())
+    }
+}
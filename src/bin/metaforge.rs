@@ -1,20 +1,332 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::Parser;
-// use metaforge::backstage::CatalogInfo;
-use metaforge::cli::Args;
+use metaforge::check::check_cycles;
+use metaforge::cli::{
+    Args, CheckArgs, Command, DependentsArgs, GenerateArgs, GraphArgs, IndexArgs, SbomArgs,
+    ScanAllArgs, SkewArgs,
+};
+use metaforge::config::Severity;
+use metaforge::dependents::{find_dependents, resolve_target};
+use metaforge::graph::DependencyGraph;
+use metaforge::index::DependencyIndex;
+use metaforge::layout::{plan_catalog_files, write_catalog_files};
+use metaforge::sbom::{ProjectSbom, SbomFormat, timestamp};
+use metaforge::scan::{
+    ScanReport, ScannedRepo, build_index, catalog_documents, scan_all, scan_repo,
+};
+use metaforge::skew::find_skew;
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let _args = Args::parse();
+    let args = Args::parse();
 
-    let mut repo = metaforge::git::GitRepo::try_from(None).await?;
-    repo.find_projects().await?;
+    let index_path = args.index.as_deref();
 
-    for project in &repo.projects {
-        // project.root
+    match args.command {
+        None => generate(&GenerateArgs::default(), index_path).await,
+        Some(Command::Generate(generate_args)) => generate(&generate_args, index_path).await,
+        Some(Command::Graph(graph_args)) => graph(&graph_args, index_path).await,
+        Some(Command::ScanAll(scan_all_args)) => scan_all_repos(&scan_all_args, index_path).await,
+        Some(Command::Index(index_args)) => update_index(&index_args, index_path).await,
+        Some(Command::Dependents(dependents_args)) => {
+            dependents(&dependents_args, index_path).await
+        }
+        Some(Command::Skew(skew_args)) => skew(&skew_args, index_path).await,
+        Some(Command::Sbom(sbom_args)) => sbom(&sbom_args, index_path).await,
+        Some(Command::Check(check_args)) => check(&check_args, index_path).await,
     }
+}
+
+/// Loads the dependency index file, if one was given, so dependencies on repositories that
+/// aren't being scanned can still be resolved.
+fn load_index(index_path: Option<&Path>) -> Result<DependencyIndex> {
+    Ok(match index_path {
+        Some(path) => DependencyIndex::load(path)?,
+        None => DependencyIndex::default(),
+    })
+}
+
+/// Prints or writes the catalog info files of the current repository.
+async fn generate(args: &GenerateArgs, index_path: Option<&Path>) -> Result<()> {
+    let scanned = scan_repo(None).await?;
+    let (repo, repo_name, config) = (&scanned.repo, &scanned.name, &scanned.config);
+
+    if args.scaffold_techdocs {
+        for project in &repo.projects {
+            let name = project.get_name(&repo.root, repo_name);
+            if metaforge::techdocs::scaffold(&project.root, &name)? {
+                eprintln!("Scaffolded TechDocs for {name}");
+            }
+        }
+    }
+
+    let mut index = load_index(index_path)?;
+    index.add_repo(repo, repo_name)?;
+    let projects = catalog_documents(&scanned, &index)?;
+
+    if !args.write {
+        let files = plan_catalog_files(&repo.root, repo_name, &projects, &config.layout)?;
+        for document in files.values().flatten() {
+            println!("---\n{document}");
+        }
+        return Ok(());
+    }
+
+    let report = write_catalog_files(
+        &repo.root,
+        repo_name,
+        &projects,
+        &config.layout,
+        args.remove_stale || config.layout.remove_stale,
+    )?;
+    for path in &report.written {
+        eprintln!("Wrote {}", path.display());
+    }
+    for path in &report.skipped {
+        eprintln!("Skipped hand-written {}", path.display());
+    }
+    for path in &report.stale {
+        if report.removed_stale {
+            eprintln!("Removed stale {}", path.display());
+        } else {
+            eprintln!("Stale {} (use --remove-stale to remove it)", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the dependency graph of the current repository's projects, or of the projects of
+/// every repository clone in a directory.
+async fn graph(args: &GraphArgs, index_path: Option<&Path>) -> Result<()> {
+    let (repos, index) = scan_repos(args.dir.as_deref(), args.concurrency, index_path).await?;
+
+    let mut graph = DependencyGraph::for_repos(&repos, &index)?;
+    if !args.include_external {
+        graph = graph.without_external();
+    }
+    if args.collapse_by_repo {
+        graph = graph.collapse_by_repo();
+    }
+
+    print!("{}", graph.render(args.format, args.highlight_cycles)?);
+
+    Ok(())
+}
+
+/// Scans every repository under a directory, resolving the dependencies between them, and
+/// outputs a bundle of all their catalog entities and a report of what was found.
+async fn scan_all_repos(args: &ScanAllArgs, index_path: Option<&Path>) -> Result<()> {
+    let (repos, failures) = scan_all(&args.dir, args.concurrency).await;
+    let mut index = load_index(index_path)?;
+    index.merge(build_index(&repos)?);
+
+    let mut bundle = String::new();
+    for scanned in &repos {
+        for project in catalog_documents(scanned, &index)? {
+            for document in project.documents {
+                bundle.push_str(&format!("---\n{document}"));
+            }
+        }
+    }
+    match &args.bundle {
+        Some(path) => std::fs::write(path, bundle)?,
+        None => print!("{bundle}"),
+    }
+
+    let report = ScanReport::new(&repos, failures, &index)?;
+    for repo in &report.repos {
+        eprintln!(
+            "{}: {} projects, {} unresolved dependencies",
+            repo.name,
+            repo.projects.len(),
+            repo.unresolved_dependencies.len()
+        );
+    }
+    for failure in &report.failures {
+        eprintln!("{}: {}", failure.path.display(), failure.error);
+    }
+    if let Some(path) = &args.report {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the packages found by scanning repositories, reading catalog info files and reading
+/// catalog exports to the dependency index file.
+async fn update_index(args: &IndexArgs, index_path: Option<&Path>) -> Result<()> {
+    let Some(index_path) = index_path else {
+        bail!("An index file must be given with --index or METAFORGE_INDEX");
+    };
+    let mut index = load_index(Some(index_path))?;
+
+    for path in &args.catalog {
+        index.add_catalog_files(path)?;
+    }
+    for path in &args.export {
+        index.add_catalog_export(path)?;
+    }
+    for dir in &args.scan {
+        let (repos, failures) = scan_all(dir, 8).await;
+        for failure in &failures {
+            eprintln!("{}: {}", failure.path.display(), failure.error);
+        }
+        index.merge(build_index(&repos)?);
+    }
+
+    index.save(index_path)?;
+    eprintln!(
+        "Indexed {} packages in {}",
+        index.entries.len(),
+        index_path.display()
+    );
+
+    Ok(())
+}
+
+/// Scans every repository clone in `dir`, or else the current repository, and indexes their
+/// packages on top of the dependency index file.
+async fn scan_repos(
+    dir: Option<&Path>,
+    concurrency: usize,
+    index_path: Option<&Path>,
+) -> Result<(Vec<ScannedRepo>, DependencyIndex)> {
+    let repos = match dir {
+        Some(dir) => {
+            let (repos, failures) = scan_all(dir, concurrency).await;
+            for failure in &failures {
+                eprintln!("{}: {}", failure.path.display(), failure.error);
+            }
+            repos
+        }
+        None => vec![scan_repo(None).await?],
+    };
+    let mut index = load_index(index_path)?;
+    index.merge(build_index(&repos)?);
+
+    Ok((repos, index))
+}
+
+/// Lists the projects that depend on a component, so breaking changes to it can be planned.
+async fn dependents(args: &DependentsArgs, index_path: Option<&Path>) -> Result<()> {
+    let (repos, index) = scan_repos(args.dir.as_deref(), args.concurrency, index_path).await?;
+
+    let target = resolve_target(&args.target, &index);
+    let dependents = find_dependents(&repos, &index, &target)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&dependents)?);
+        return Ok(());
+    }
+
+    eprintln!("{} dependents of {target}", dependents.len());
+    for dependent in &dependents {
+        let relation = match &dependent.via {
+            Some(via) => format!("transitive via {via}"),
+            None => "direct".to_string(),
+        };
+        let versions = if dependent.versions.is_empty() {
+            "unversioned".to_string()
+        } else {
+            dependent.versions.join(", ")
+        };
+        println!(
+            "{}\t{}/{}\t{relation}\t{versions}",
+            dependent.entity_ref,
+            dependent.repo,
+            dependent.path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Lists the internal components required at different versions across the scanned projects.
+async fn skew(args: &SkewArgs, index_path: Option<&Path>) -> Result<()> {
+    let (repos, index) = scan_repos(args.dir.as_deref(), args.concurrency, index_path).await?;
+    let skews = find_skew(&repos, &index, args.min_versions)?;
 
-    println!("{repo:?}");
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&skews)?);
+        return Ok(());
+    }
+
+    for skew in &skews {
+        println!("{}: {} versions", skew.entity_ref, skew.versions.len());
+        for (version, consumers) in &skew.versions {
+            println!("  {version}: {}", consumers.join(", "));
+        }
+        if !skew.unversioned.is_empty() {
+            println!("  unversioned: {}", skew.unversioned.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a software bill of materials for each of the current repository's projects.
+async fn sbom(args: &SbomArgs, index_path: Option<&Path>) -> Result<()> {
+    let scanned = scan_repo(None).await?;
+    let mut index = load_index(index_path)?;
+    index.add_repo(&scanned.repo, &scanned.name)?;
+
+    std::fs::create_dir_all(&args.output_dir)?;
+    let created = timestamp();
+    for project in &scanned.repo.projects {
+        let sbom = ProjectSbom::for_project(
+            &scanned.repo,
+            &scanned.name,
+            project,
+            &scanned.config,
+            &index,
+        )?;
+
+        let mut documents = vec![];
+        if matches!(args.format, SbomFormat::CycloneDx | SbomFormat::All) {
+            documents.push((
+                format!("{}.cdx.json", sbom.name),
+                serde_json::to_string_pretty(&sbom.to_cyclonedx(&created))?,
+            ));
+        }
+        if matches!(args.format, SbomFormat::Spdx | SbomFormat::All) {
+            documents.push((
+                format!("{}.spdx.json", sbom.name),
+                serde_json::to_string_pretty(&sbom.to_spdx(&created))?,
+            ));
+        }
+
+        for (file_name, document) in documents {
+            let path = args.output_dir.join(file_name);
+            std::fs::write(&path, document)?;
+            eprintln!("Wrote {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Reports the dependency cycles between internal components, failing if any is an error.
+async fn check(args: &CheckArgs, index_path: Option<&Path>) -> Result<()> {
+    let (repos, index) = scan_repos(args.dir.as_deref(), args.concurrency, index_path).await?;
+    let cycles = check_cycles(&repos, &index)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&cycles)?);
+    } else {
+        for cycle in &cycles {
+            eprintln!("{cycle}");
+        }
+    }
+
+    let errors = cycles
+        .iter()
+        .filter(|cycle| cycle.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        bail!("{errors} dependency cycles are errors");
+    }
 
     Ok(())
 }
@@ -0,0 +1,8 @@
+use crate::graph::BuildGraphError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CheckCyclesError {
+    #[error("Failed to build a repository's dependency graph: {0}")]
+    BuildGraph(#[from] BuildGraphError),
+}
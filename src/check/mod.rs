@@ -0,0 +1,154 @@
+mod errors;
+
+use crate::config::Severity;
+use crate::graph::DependencyGraph;
+use crate::index::DependencyIndex;
+use crate::scan::ScannedRepo;
+pub use errors::CheckCyclesError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A dependency cycle between internal components.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DependencyCycle {
+    /// How the cycle is reported, the strictest of the severities configured by the
+    /// repositories it passes through
+    pub severity: Severity,
+
+    /// The entity refs around the cycle, starting and ending with the same component
+    pub path: Vec<String>,
+
+    /// Whether the cycle passes through more than one repository
+    #[serde(rename = "crossRepo")]
+    pub cross_repo: bool,
+}
+
+impl std::fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let scope = if self.cross_repo {
+            "cross-repo"
+        } else {
+            "intra-repo"
+        };
+        write!(
+            f,
+            "{}: {scope} dependency cycle: {}",
+            self.severity,
+            self.path.join(" -> ")
+        )
+    }
+}
+
+/// Finds the dependency cycles between the projects of the scanned repositories, both within
+/// a repository and across them. Cycles that every repository they pass through has turned off
+/// in its `checks.cycles` config aren't reported.
+pub fn check_cycles(
+    repos: &[ScannedRepo],
+    index: &DependencyIndex,
+) -> Result<Vec<DependencyCycle>, CheckCyclesError> {
+    let graph = DependencyGraph::for_repos(repos, index)?;
+    let severities: BTreeMap<&str, Severity> = repos
+        .iter()
+        .map(|scanned| (scanned.name.as_str(), scanned.config.checks.cycles))
+        .collect();
+
+    Ok(graph
+        .cycles()
+        .into_iter()
+        .filter_map(|cycle| {
+            let repos: Vec<&str> = cycle
+                .iter()
+                .filter_map(|id| graph.nodes.get(id)?.repo.as_deref())
+                .collect();
+            let severity = repos
+                .iter()
+                .filter_map(|repo| severities.get(repo).copied())
+                .max()
+                .unwrap_or_default();
+            if severity == Severity::Off {
+                return None;
+            }
+
+            Some(DependencyCycle {
+                severity,
+                path: graph.cycle_path(&cycle),
+                cross_repo: repos.iter().any(|repo| *repo != repos[0]),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{build_index, scan_repo};
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_check_cycles() -> Result<()> {
+        let test_dir = tempdir()?;
+
+        for (repo, config, modules) in [
+            (
+                "shop",
+                "",
+                vec![
+                    ("orders", "bitbucket.org/bxbdigital/ledger v1.0.0"),
+                    ("cart", "bitbucket.org/bxbdigital/basket v0.1.0"),
+                    ("basket", "bitbucket.org/bxbdigital/cart v0.1.0"),
+                ],
+            ),
+            (
+                "finance",
+                "checks:\n  cycles: error\n",
+                vec![("ledger", "bitbucket.org/bxbdigital/orders v2.0.0")],
+            ),
+        ] {
+            let repo_root = test_dir.path().join(repo);
+            std::fs::create_dir_all(repo_root.join(".git"))?;
+            std::fs::write(repo_root.join(".metaforge.yaml"), config)?;
+            for (module, require) in modules {
+                std::fs::create_dir(repo_root.join(module))?;
+                std::fs::write(
+                    repo_root.join(module).join("go.mod"),
+                    format!(
+                        "module bitbucket.org/bxbdigital/{module}\n\nrequire (\n\t{require}\n)\n"
+                    ),
+                )?;
+            }
+        }
+
+        let repos = vec![
+            scan_repo(Some(test_dir.path().join("shop"))).await?,
+            scan_repo(Some(test_dir.path().join("finance"))).await?,
+        ];
+        let index = build_index(&repos)?;
+
+        assert_eq!(
+            check_cycles(&repos, &index)?,
+            vec![
+                DependencyCycle {
+                    severity: Severity::Warning,
+                    path: vec![
+                        "component:default/basket".to_string(),
+                        "component:default/cart".to_string(),
+                        "component:default/basket".to_string(),
+                    ],
+                    cross_repo: false,
+                },
+                DependencyCycle {
+                    severity: Severity::Error,
+                    path: vec![
+                        "component:default/ledger".to_string(),
+                        "component:default/orders".to_string(),
+                        "component:default/ledger".to_string(),
+                    ],
+                    cross_repo: true,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
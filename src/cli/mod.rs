@@ -1,5 +1,181 @@
-use clap::Parser;
+use crate::graph::GraphFormat;
+use crate::sbom::SbomFormat;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-pub struct Args {}
+pub struct Args {
+    /// What to do, generating the catalog info files when not given
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// A dependency index file used to resolve dependencies on other repositories
+    #[arg(long, global = true, env = "METAFORGE_INDEX")]
+    pub index: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate the Backstage catalog info files of the repository
+    Generate(GenerateArgs),
+
+    /// Output the graph of the repository's projects and their dependencies
+    Graph(GraphArgs),
+
+    /// Scan every git repository under a directory and bundle their catalog info
+    ScanAll(ScanAllArgs),
+
+    /// Add packages and the entities owning them to the dependency index file
+    Index(IndexArgs),
+
+    /// List the projects that depend on a component, directly or transitively
+    Dependents(DependentsArgs),
+
+    /// List the internal components that projects require at different versions
+    Skew(SkewArgs),
+
+    /// Write a CycloneDX and SPDX software bill of materials for each project
+    Sbom(SbomArgs),
+
+    /// Check for dependency cycles between internal components
+    Check(CheckArgs),
+}
+
+#[derive(clap::Args, Debug, Default)]
+pub struct GenerateArgs {
+    /// Write the catalog info files into the repository instead of printing them
+    #[arg(long)]
+    pub write: bool,
+
+    /// Remove catalog info files of projects that no longer exist, when writing
+    #[arg(long)]
+    pub remove_stale: bool,
+
+    /// Scaffold a minimal mkdocs.yml and docs/index.md for projects without TechDocs
+    #[arg(long)]
+    pub scaffold_techdocs: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct GraphArgs {
+    /// The format to output the graph in
+    #[arg(long, value_enum, default_value = "dot")]
+    pub format: GraphFormat,
+
+    /// Include dependencies on components outside the scanned projects
+    #[arg(long)]
+    pub include_external: bool,
+
+    /// Merge the projects of each repository into a single node
+    #[arg(long)]
+    pub collapse_by_repo: bool,
+
+    /// Colour the dependency cycles red
+    #[arg(long)]
+    pub highlight_cycles: bool,
+
+    /// Graph every repository clone in this directory instead of the current repository
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// How many repositories to scan at once
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ScanAllArgs {
+    /// The directory containing the repository clones
+    pub dir: PathBuf,
+
+    /// How many repositories to scan at once
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+
+    /// Write the catalog bundle to this file instead of printing it
+    #[arg(long)]
+    pub bundle: Option<PathBuf>,
+
+    /// Write the JSON scan report to this file
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct IndexArgs {
+    /// Directories of repository clones to scan
+    #[arg(long)]
+    pub scan: Vec<PathBuf>,
+
+    /// Catalog info files, or directories containing them
+    #[arg(long)]
+    pub catalog: Vec<PathBuf>,
+
+    /// JSON exports of a Backstage catalog
+    #[arg(long)]
+    pub export: Vec<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DependentsArgs {
+    /// The entity ref, entity name or package name of the component, e.g. a Go module path
+    pub target: String,
+
+    /// Search every repository clone in this directory instead of the current repository
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// How many repositories to scan at once
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+
+    /// Print the dependents as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SkewArgs {
+    /// Search every repository clone in this directory instead of the current repository
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// How many repositories to scan at once
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+
+    /// Only list components required at least this many different versions
+    #[arg(long, default_value_t = 2)]
+    pub min_versions: usize,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SbomArgs {
+    /// The format of the bills of materials
+    #[arg(long, value_enum, default_value = "all")]
+    pub format: SbomFormat,
+
+    /// The directory the bills of materials are written to
+    #[arg(long, default_value = "sbom")]
+    pub output_dir: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CheckArgs {
+    /// Check every repository clone in this directory instead of the current repository
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// How many repositories to scan at once
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+
+    /// Print the problems found as JSON
+    #[arg(long)]
+    pub json: bool,
+}
@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LoadCodeOwnersError {
+    #[error("Failed to read the CODEOWNERS file {path}: {source}")]
+    FailedToReadCodeOwnersFile {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to compile the CODEOWNERS pattern {pattern}: {source}")]
+    FailedToCompilePatternRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+}
@@ -0,0 +1,281 @@
+mod errors;
+
+use crate::backstage::entity_name;
+use crate::config::OwnersConfig;
+pub use errors::LoadCodeOwnersError;
+use regex::Regex;
+use std::path::Path;
+
+/// The places GitHub, GitLab and Bitbucket look for a CODEOWNERS file, in the order they are
+/// searched. Only the first one found is used.
+const CODEOWNERS_FILES: [&str; 5] = [
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+    ".bitbucket/CODEOWNERS",
+];
+
+/// A line of a CODEOWNERS file assigning owners to the paths matching a pattern.
+#[derive(Debug, Clone)]
+pub struct CodeOwnersRule {
+    pub pattern: String,
+    pub owners: Vec<String>,
+    regex: Regex,
+
+    /// Whether the rule only covers the files directly inside the directory it names, as with
+    /// `docs/*`, rather than its subdirectories as well
+    direct_children_only: bool,
+}
+
+/// The rules of a repository's CODEOWNERS file.
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    pub rules: Vec<CodeOwnersRule>,
+}
+
+impl CodeOwners {
+    /// Loads the CODEOWNERS file of the repository, returning no rules if there isn't one.
+    pub fn load(repo_root: &Path) -> Result<CodeOwners, LoadCodeOwnersError> {
+        let Some(codeowners_path) = CODEOWNERS_FILES
+            .iter()
+            .map(|file| repo_root.join(file))
+            .find(|path| path.is_file())
+        else {
+            return Ok(CodeOwners::default());
+        };
+
+        let codeowners_text = std::fs::read_to_string(&codeowners_path).map_err(|source| {
+            LoadCodeOwnersError::FailedToReadCodeOwnersFile {
+                path: format!("{}", codeowners_path.display()),
+                source,
+            }
+        })?;
+
+        CodeOwners::parse(&codeowners_text)
+    }
+
+    /// Parses the rules of a CODEOWNERS file, skipping comments, GitLab `[Section]` headers and
+    /// Bitbucket reviewer selection functions such as `Random(2, @@team)`.
+    pub fn parse(text: &str) -> Result<CodeOwners, LoadCodeOwnersError> {
+        let mut rules = vec![];
+        for line in text.lines() {
+            let line = line.split(" #").next().unwrap_or("").trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let Some(pattern) = tokens.next() else {
+                continue;
+            };
+            let owners = tokens
+                .filter(|owner| !owner.contains('(') && !owner.contains(')'))
+                .map(|owner| owner.trim_end_matches(',').to_string())
+                .collect();
+
+            rules.push(CodeOwnersRule {
+                pattern: pattern.to_string(),
+                owners,
+                regex: pattern_regex(pattern)?,
+                direct_children_only: pattern.ends_with("/*") && !pattern.ends_with("/**"),
+            });
+        }
+
+        Ok(CodeOwners { rules })
+    }
+
+    /// The owners of a directory, given relative to the repository root. As in git, the last
+    /// matching rule takes precedence, and a rule with no owners leaves the path unowned.
+    pub fn owners_of(&self, relative_dir: &Path) -> Option<&[String]> {
+        let relative_dir = relative_dir.to_string_lossy().replace('\\', "/");
+        let relative_dir = relative_dir.trim_matches('/');
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                if rule.direct_children_only {
+                    return rule.regex.is_match(relative_dir);
+                }
+
+                // a rule for a directory or any of its parents covers the whole directory
+                let mut dir = relative_dir;
+                loop {
+                    if rule.regex.is_match(dir) {
+                        return true;
+                    }
+                    match dir.rfind('/') {
+                        Some(index) => dir = &dir[..index],
+                        None if !dir.is_empty() => dir = "",
+                        None => return false,
+                    }
+                }
+            })
+            .map(|rule| rule.owners.as_slice())
+            .filter(|owners| !owners.is_empty())
+    }
+
+    /// The Backstage owner of a directory: the first of its CODEOWNERS owners mapped through
+    /// `config`, or the configured default owner if nothing matches.
+    pub fn owner_of(&self, relative_dir: &Path, config: &OwnersConfig) -> String {
+        self.owners_of(relative_dir)
+            .and_then(|owners| owners.first())
+            .map(|owner| owner_ref(owner, config))
+            .unwrap_or_else(|| config.default.clone())
+    }
+}
+
+/// Converts a CODEOWNERS owner to a Backstage entity ref, using the mapping in `config` when
+/// there is one. Teams (`@org/team`) and Bitbucket groups (`@@group`) become groups, while
+/// usernames and email addresses become users.
+pub fn owner_ref(owner: &str, config: &OwnersConfig) -> String {
+    if let Some(entity_ref) = config.mappings.get(owner) {
+        return entity_ref.clone();
+    }
+
+    if let Some(group) = owner.strip_prefix("@@") {
+        return format!(
+            "group:default/{}",
+            entity_name(group.trim_start_matches('@'))
+        );
+    }
+    if let Some(user) = owner.strip_prefix('@') {
+        return match user.rsplit_once('/') {
+            Some((_, team)) => format!("group:default/{}", entity_name(team)),
+            None => format!("user:default/{}", entity_name(user)),
+        };
+    }
+
+    let user = owner.split('@').next().unwrap_or(owner);
+    format!("user:default/{}", entity_name(user))
+}
+
+/// Converts a gitignore style CODEOWNERS pattern to a regex matching the paths it covers.
+/// Patterns without a slash match at any depth. Patterns ending in `/**` own the directory they
+/// name and everything below it, while those ending in `/*` match the directory they name but
+/// only own the files directly inside it. A `**/` matches any number of directories, including
+/// none, so `**/foo` also matches `foo` at the repository root.
+fn pattern_regex(pattern: &str) -> Result<Regex, LoadCodeOwnersError> {
+    // a slash anywhere but the end anchors the pattern to the repository root
+    let anchored = pattern.trim_end_matches('/').contains('/');
+    let trimmed = pattern
+        .trim_end_matches("/**")
+        .trim_end_matches("/*")
+        .trim_end_matches('/');
+    let trimmed = trimmed.trim_start_matches('/');
+
+    let mut regex = String::from(if anchored || trimmed.is_empty() {
+        "^"
+    } else {
+        "^(?:.*/)?"
+    });
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+
+    Regex::new(&regex).map_err(|source| LoadCodeOwnersError::FailedToCompilePatternRegex {
+        pattern: pattern.to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use std::collections::BTreeMap;
+
+    const CODEOWNERS: &str = r#"# default owners
+*                   @bxbdigital/platform
+
+[Payments]
+/services/payments/ @bxbdigital/payments alice@bxbdigital.com
+ledger              @@finance
+/services/ledger/legacy/
+docs/**             @bob
+config/*            @dave
+**/migrations       @carol
+"#;
+
+    #[test]
+    fn test_owners_of() -> Result<()> {
+        let codeowners = CodeOwners::parse(CODEOWNERS)?;
+
+        let owners = |dir: &str| codeowners.owners_of(Path::new(dir)).map(|o| o.to_vec());
+
+        assert_eq!(owners(""), Some(vec!["@bxbdigital/platform".to_string()]));
+        assert_eq!(
+            owners("services/payments/api"),
+            Some(vec![
+                "@bxbdigital/payments".to_string(),
+                "alice@bxbdigital.com".to_string()
+            ])
+        );
+        assert_eq!(
+            owners("services/ledger"),
+            Some(vec!["@@finance".to_string()])
+        );
+        assert_eq!(owners("services/ledger/legacy"), None);
+        assert_eq!(owners("docs"), Some(vec!["@bob".to_string()]));
+        assert_eq!(owners("docs/nested/deeper"), Some(vec!["@bob".to_string()]));
+        assert_eq!(owners("config"), Some(vec!["@dave".to_string()]));
+        assert_eq!(
+            owners("config/nested/deeper"),
+            Some(vec!["@bxbdigital/platform".to_string()])
+        );
+        assert_eq!(
+            owners("services/docs"),
+            Some(vec!["@bxbdigital/platform".to_string()])
+        );
+        assert_eq!(owners("migrations"), Some(vec!["@carol".to_string()]));
+        assert_eq!(
+            owners("services/ledger/migrations"),
+            Some(vec!["@carol".to_string()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_owner_of() -> Result<()> {
+        let codeowners = CodeOwners::parse(CODEOWNERS)?;
+        let config = OwnersConfig {
+            default: "group:default/unowned".to_string(),
+            mappings: BTreeMap::from([(
+                "@bxbdigital/payments".to_string(),
+                "group:default/payments-team".to_string(),
+            )]),
+            ..OwnersConfig::default()
+        };
+
+        let owner = |dir: &str| codeowners.owner_of(Path::new(dir), &config);
+
+        assert_eq!(owner("services/payments"), "group:default/payments-team");
+        assert_eq!(owner("services/ledger"), "group:default/finance");
+        assert_eq!(owner("services/ledger/legacy"), "group:default/unowned");
+        assert_eq!(owner("docs"), "user:default/bob");
+        assert_eq!(owner("worker"), "group:default/platform");
+        assert_eq!(
+            owner_ref("alice@bxbdigital.com", &config),
+            "user:default/alice"
+        );
+
+        Ok(())
+    }
+}
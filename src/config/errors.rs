@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LoadConfigError {
+    #[error("Couldn't read the metaforge config file: {0}")]
+    ReadConfigFile(#[from] std::io::Error),
+
+    #[error("Could not parse the metaforge config file: {0}")]
+    ParseConfigFile(#[from] serde_yml::Error),
+}
@@ -0,0 +1,411 @@
+mod errors;
+
+use crate::backstage::entity_name;
+pub use errors::LoadConfigError;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = ".metaforge.yaml";
+
+/// The metaforge configuration, read from `.metaforge.yaml` in the repository root.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Config {
+    /// What counts as one of our own repositories or packages
+    pub internal: InternalConfig,
+
+    /// Settings for the Dockerfile analyser
+    pub docker: DockerConfig,
+
+    /// Overrides for the inferred component types
+    #[serde(rename = "componentTypes")]
+    pub component_types: ComponentTypesConfig,
+
+    /// How CODEOWNERS owners map to Backstage owners
+    pub owners: OwnersConfig,
+
+    /// Where the catalog info files are written
+    pub layout: LayoutConfig,
+
+    /// The System the repository's components belong to
+    pub system: SystemConfig,
+
+    /// What is recorded about each component's internal dependencies
+    pub dependencies: DependenciesConfig,
+
+    /// How the problems found by the `check` command are reported
+    pub checks: ChecksConfig,
+}
+
+/// What counts as one of our own repositories or packages, shared by every language.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct InternalConfig {
+    /// The git hosts our repositories live on, e.g. `bitbucket.org`
+    pub hosts: Vec<String>,
+
+    /// The organisations owning our repositories on those hosts. They are also the scope of
+    /// our npm packages, the vendor of our Composer packages, our Hex organisation and a
+    /// segment of our Maven group ids
+    pub orgs: Vec<String>,
+
+    /// The domains of our private package registries, e.g. `bxbdigital.io`
+    pub domains: Vec<String>,
+}
+
+impl Default for InternalConfig {
+    fn default() -> Self {
+        InternalConfig {
+            hosts: vec!["bitbucket.org".to_string()],
+            orgs: vec!["bxbdigital".to_string()],
+            domains: vec!["bxbdigital.io".to_string()],
+        }
+    }
+}
+
+impl InternalConfig {
+    /// Compiles the patterns matching our repositories and packages.
+    pub fn compile(&self) -> Result<InternalPatterns, regex::Error> {
+        let alternatives = |values: &[String]| {
+            let escaped: Vec<String> = values.iter().map(|value| regex::escape(value)).collect();
+            // an empty alternation would match everything, so match nothing instead
+            if escaped.is_empty() {
+                r#"[^\s\S]"#.to_string()
+            } else {
+                escaped.join("|")
+            }
+        };
+        let (hosts, orgs, domains) = (
+            alternatives(&self.hosts),
+            alternatives(&self.orgs),
+            alternatives(&self.domains),
+        );
+
+        Ok(InternalPatterns {
+            orgs: self.orgs.clone(),
+            repo: Regex::new(&format!(
+                r#"(?:^|[/@])(?:{hosts})[/:](?:{orgs})/([^/?#]+?)(?:\.git)?(?:[/?#]|$)"#
+            ))?,
+            registry: Regex::new(&format!(r#"(?:^|[/.@])(?:{domains})(?:[:/]|$)"#))?,
+            scope: Regex::new(&format!(r#"^@(?:{orgs})/"#))?,
+            vendor: Regex::new(&format!(r#"^(?:{orgs})/"#))?,
+            group: Regex::new(&format!(r#"(?:^|\.)(?:{orgs})(?:\.|$)"#))?,
+            image: Regex::new(&format!(
+                r#"^(?:(?:[\w-]+\.)*(?:{domains})(?::\d+)?/|(?:[^/]+/)?(?:{orgs})/)"#
+            ))?,
+            buf_module: Regex::new(&format!(
+                r#"^(?:buf\.build|(?:[\w-]+\.)*(?:{domains}))/(?:{orgs})/([^/:]+)"#
+            ))?,
+        })
+    }
+}
+
+/// The compiled [`InternalConfig`], deciding which repositories and packages are our own.
+#[derive(Debug, Clone)]
+pub struct InternalPatterns {
+    orgs: Vec<String>,
+    repo: Regex,
+    registry: Regex,
+    scope: Regex,
+    vendor: Regex,
+    group: Regex,
+    image: Regex,
+    buf_module: Regex,
+}
+
+impl InternalPatterns {
+    /// Whether `url` points into one of our repositories, e.g.
+    /// `git@bitbucket.org:bxbdigital/ledger.git` or the Go module `bitbucket.org/bxbdigital/ledger`.
+    pub fn is_internal_repo(&self, url: &str) -> bool {
+        self.repo.is_match(url)
+    }
+
+    /// The name of the repository `url` points into, if it is one of ours.
+    pub fn repo_name<'a>(&self, url: &'a str) -> Option<&'a str> {
+        Some(self.repo.captures(url)?.get(1)?.as_str())
+    }
+
+    /// Whether `url` is one of our repositories or one of our private package registries, e.g.
+    /// `https://pypi.bxbdigital.io/simple`.
+    pub fn is_internal_source(&self, url: &str) -> bool {
+        self.is_internal_repo(url) || self.registry.is_match(url)
+    }
+
+    /// Whether `package` is in one of our npm scopes, e.g. `@bxbdigital/ui`.
+    pub fn is_internal_scope(&self, package: &str) -> bool {
+        self.scope.is_match(package)
+    }
+
+    /// Whether `package` is published by one of our Composer vendors, e.g. `bxbdigital/auth`.
+    pub fn is_internal_vendor(&self, package: &str) -> bool {
+        self.vendor.is_match(package)
+    }
+
+    /// Whether the Maven `group_id` has one of our organisations as a segment, e.g.
+    /// `com.bxbdigital.shared`.
+    pub fn is_internal_group(&self, group_id: &str) -> bool {
+        self.group.is_match(group_id)
+    }
+
+    /// Whether the container `image` is hosted on one of our registries or published by one of
+    /// our organisations, e.g. `registry.bxbdigital.io/platform/base` or `bxbdigital/tools`.
+    pub fn is_internal_image(&self, image: &str) -> bool {
+        self.image.is_match(image)
+    }
+
+    /// The name of the buf module `dep` refers to, if it is published by one of our
+    /// organisations, e.g. `common` for `buf.build/bxbdigital/common:v1.2.0`.
+    pub fn buf_module_name<'a>(&self, dep: &'a str) -> Option<&'a str> {
+        Some(self.buf_module.captures(dep)?.get(1)?.as_str())
+    }
+
+    /// Whether `org` is one of our organisations, e.g. a Hex organisation.
+    pub fn is_internal_org(&self, org: &str) -> bool {
+        self.orgs.iter().any(|internal| internal == org)
+    }
+}
+
+/// Settings for the Dockerfile analyser.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DockerConfig {
+    /// Regexes matching registries that host our own images besides those on the
+    /// [`InternalConfig`] domains and orgs
+    #[serde(rename = "internalRegistries")]
+    pub internal_registries: Vec<String>,
+
+    /// Maps image repository paths to the name of the component that produces them
+    pub images: BTreeMap<String, String>,
+}
+
+/// Overrides for the component `spec.type` inferred for each project.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ComponentTypesConfig {
+    /// Maps the name of an inference rule, e.g. `rust-binary`, to the type it should produce
+    pub rules: BTreeMap<String, String>,
+
+    /// Maps component names to their type, taking precedence over any rule
+    pub components: BTreeMap<String, String>,
+}
+
+/// How the owners found in CODEOWNERS files map to Backstage entity refs.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct OwnersConfig {
+    /// The owner of projects that no CODEOWNERS rule matches
+    pub default: String,
+
+    /// Maps CODEOWNERS owners, e.g. `@bxbdigital/payments`, to entity refs such as
+    /// `group:default/payments`
+    pub mappings: BTreeMap<String, String>,
+
+    /// Regexes matching the names or emails of bots, whose commits are ignored when suggesting
+    /// an owner from the git history
+    pub bots: Vec<String>,
+
+    /// A YAML file, relative to the repository root, mapping author emails to entity refs
+    #[serde(rename = "mappingFile")]
+    pub mapping_file: String,
+
+    /// How many days it takes for a commit to count half as much towards ownership
+    #[serde(rename = "halfLifeDays")]
+    pub half_life_days: u32,
+}
+
+impl Default for OwnersConfig {
+    fn default() -> Self {
+        OwnersConfig {
+            default: "unknown".to_string(),
+            mappings: BTreeMap::new(),
+            bots: vec![
+                r#"(?i)\[bot\]"#.to_string(),
+                r#"(?i)^(dependabot|renovate|github-actions|bitbucket-pipelines|jenkins)"#
+                    .to_string(),
+            ],
+            mapping_file: ".metaforge-owners.yaml".to_string(),
+            half_life_days: 180,
+        }
+    }
+}
+
+/// Where catalog info files are written in a repository with many projects.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct LayoutConfig {
+    pub mode: LayoutMode,
+
+    /// Whether catalog info files of projects that no longer exist are removed
+    #[serde(rename = "removeStale")]
+    pub remove_stale: bool,
+}
+
+/// How the entities of a repository are split between catalog info files.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum LayoutMode {
+    /// A catalog info file in each project root, listed by a `Location` in the root file
+    #[default]
+    #[serde(rename = "perProject")]
+    PerProject,
+
+    /// Every entity in a single catalog info file in the repository root
+    #[serde(rename = "single")]
+    Single,
+}
+
+/// What is recorded about the internal dependencies of components.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DependenciesConfig {
+    /// Whether to annotate components with the version and source of each internal dependency
+    #[serde(rename = "annotateVersions")]
+    pub annotate_versions: bool,
+}
+
+/// How the problems found by the `check` command are reported.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ChecksConfig {
+    /// How dependency cycles between internal components are reported
+    pub cycles: Severity,
+}
+
+/// How a problem found by a check is reported.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The problem isn't reported
+    #[serde(rename = "off")]
+    Off,
+
+    /// The problem is reported without failing the check
+    #[default]
+    #[serde(rename = "warning")]
+    Warning,
+
+    /// The problem is reported and fails the check
+    #[serde(rename = "error")]
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Off => write!(f, "off"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The Backstage System generated for a repository, and the Domain it belongs to.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SystemConfig {
+    /// Whether to generate a `System` entity for the repository
+    pub generate: bool,
+
+    /// The name of the system, defaulting to the name of the repository
+    pub name: Option<String>,
+
+    /// The description of the system
+    pub description: Option<String>,
+
+    /// The owner of the system, defaulting to the owner of the repository root
+    pub owner: Option<String>,
+
+    /// The domain the system belongs to
+    pub domain: Option<String>,
+
+    /// Whether to also generate the `Domain` entity, when it isn't declared elsewhere
+    #[serde(rename = "generateDomain")]
+    pub generate_domain: bool,
+}
+
+impl SystemConfig {
+    /// The system the repository's components belong to, if a system is generated or named.
+    pub fn system_name(&self, repo_name: &str) -> Option<String> {
+        match &self.name {
+            Some(name) => Some(entity_name(name)),
+            None if self.generate => Some(entity_name(repo_name)),
+            None => None,
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// The directory holding the catalog info file of the project at `project_root`.
+    pub fn catalog_dir(&self, repo_root: &Path, project_root: &Path) -> PathBuf {
+        match self.mode {
+            LayoutMode::PerProject => project_root.to_path_buf(),
+            LayoutMode::Single => repo_root.to_path_buf(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file from the repository root, falling back to the defaults if there is
+    /// no config file.
+    pub fn load(repo_root: &Path) -> Result<Config, LoadConfigError> {
+        let config_path = repo_root.join(CONFIG_FILE);
+        if !config_path.exists() {
+            return Ok(Config::default());
+        }
+
+        let config_file = std::fs::File::open(config_path)?;
+        Ok(serde_yml::from_reader(config_file)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+
+    #[test]
+    fn test_internal_patterns() -> Result<()> {
+        let internal = InternalConfig::default().compile()?;
+
+        assert!(internal.is_internal_repo("git@bitbucket.org:bxbdigital/ledger.git"));
+        assert!(internal.is_internal_repo("bitbucket.org/bxbdigital/ledger/v3"));
+        assert!(!internal.is_internal_repo("github.com/bxbdigital-fans/ledger"));
+        assert!(!internal.is_internal_repo("https://notbitbucket.org/bxbdigital/ledger"));
+        assert_eq!(
+            internal.repo_name("https://bitbucket.org/bxbdigital/base//deploy?ref=v1"),
+            Some("base")
+        );
+
+        assert!(internal.is_internal_source("https://pypi.bxbdigital.io/simple"));
+        assert!(!internal.is_internal_source("https://pypi.org/simple/bxbdigital-utils"));
+
+        assert!(internal.is_internal_scope("@bxbdigital/ui"));
+        assert!(!internal.is_internal_scope("@bxbdigital-labs/ui"));
+        assert!(internal.is_internal_vendor("bxbdigital/auth-bundle"));
+        assert!(internal.is_internal_group("com.bxbdigital.shared"));
+        assert!(!internal.is_internal_group("com.notbxbdigital"));
+        assert!(internal.is_internal_image("registry.bxbdigital.io:5000/platform/base:1"));
+        assert!(internal.is_internal_image("ghcr.io/bxbdigital/base"));
+        assert!(!internal.is_internal_image("bxbdigital-fans/base"));
+        assert!(!internal.is_internal_image("docker.io/library/bxbdigital"));
+        assert_eq!(
+            internal.buf_module_name("buf.build/bxbdigital/common:v1.2.0"),
+            Some("common")
+        );
+        assert_eq!(
+            internal.buf_module_name("buf.build/bxbdigital-fans/common"),
+            None
+        );
+
+        let nothing = InternalConfig {
+            hosts: vec![],
+            orgs: vec![],
+            domains: vec![],
+        }
+        .compile()?;
+        assert!(!nothing.is_internal_source("https://bitbucket.org/bxbdigital/ledger"));
+        assert!(!nothing.is_internal_scope("@bxbdigital/ui"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+mod errors;
+
+use crate::backstage::Component;
+use crate::index::DependencyIndex;
+use crate::scan::ScannedRepo;
+pub use errors::FindDependentsError;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::PathBuf;
+
+/// A project that depends on a component, directly or through other projects.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Dependent {
+    /// The entity ref of the project, e.g. `component:default/checkout`
+    #[serde(rename = "entityRef")]
+    pub entity_ref: String,
+
+    pub repo: String,
+
+    /// The directory of the project relative to its repository root
+    pub path: PathBuf,
+
+    /// Whether the project declares the dependency itself
+    pub direct: bool,
+
+    /// For transitive dependents, the component the project depends on that leads to the target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+
+    /// The versions the project requires of the target, or of `via` for transitive dependents
+    pub versions: Vec<String>,
+}
+
+/// A project's dependency on a component.
+struct Edge {
+    dependent: String,
+    versions: Vec<String>,
+}
+
+/// Resolves an entity ref, an entity name or a published package name, e.g. a Go module path,
+/// to the entity ref of the component it refers to.
+pub fn resolve_target(target: &str, index: &DependencyIndex) -> String {
+    if target.starts_with("component:") {
+        return target.to_string();
+    }
+    index.resolve(&Component::at(target, None))
+}
+
+/// Finds every project in the scanned repositories that depends on `target`, closest first.
+/// Transitive dependents are found by walking the dependency graph backwards from the target,
+/// so each is reported through the shortest path that reaches it.
+pub fn find_dependents(
+    repos: &[ScannedRepo],
+    index: &DependencyIndex,
+    target: &str,
+) -> Result<Vec<Dependent>, FindDependentsError> {
+    let mut projects: BTreeMap<String, (String, PathBuf)> = BTreeMap::new();
+    let mut edges: BTreeMap<String, Vec<Edge>> = BTreeMap::new();
+
+    for scanned in repos {
+        let repo = &scanned.repo;
+        for project in &repo.projects {
+            let self_ref = project.get_entity_ref(&repo.root, &scanned.name);
+            let path = project
+                .root
+                .strip_prefix(&repo.root)
+                .unwrap_or(&project.root)
+                .to_path_buf();
+            projects.insert(self_ref.clone(), (scanned.name.clone(), path));
+
+            // transitive dependents are found by walking the graph, not from lockfiles
+            for dependency in project
+                .get_internal_dependencies(&self_ref, &scanned.config, index)?
+                .into_iter()
+                .filter(|dependency| !dependency.indirect)
+            {
+                edges.entry(dependency.entity_ref).or_default().push(Edge {
+                    dependent: self_ref.clone(),
+                    versions: dependency.versions,
+                });
+            }
+        }
+    }
+
+    let mut dependents = vec![];
+    let mut visited = BTreeSet::from([target.to_string()]);
+    let mut queue = VecDeque::from([target.to_string()]);
+    while let Some(dependency) = queue.pop_front() {
+        for edge in edges.get(&dependency).into_iter().flatten() {
+            if !visited.insert(edge.dependent.clone()) {
+                continue;
+            }
+            queue.push_back(edge.dependent.clone());
+
+            let Some((repo, path)) = projects.get(&edge.dependent) else {
+                continue;
+            };
+            let direct = dependency == target;
+            dependents.push(Dependent {
+                entity_ref: edge.dependent.clone(),
+                repo: repo.clone(),
+                path: path.clone(),
+                direct,
+                via: (!direct).then(|| dependency.clone()),
+                versions: edge.versions.clone(),
+            });
+        }
+    }
+
+    Ok(dependents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_repo;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_find_dependents() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+
+        let go_mod = |module: &str, requires: &str| {
+            format!("module bitbucket.org/bxbdigital/{module}\n\nrequire (\n{requires})\n")
+        };
+        for (module, requires) in [
+            ("ledger", ""),
+            ("payments", "\tbitbucket.org/bxbdigital/ledger v1.4.0\n"),
+            ("checkout", "\tbitbucket.org/bxbdigital/payments v0.9.2\n"),
+        ] {
+            std::fs::create_dir(test_dir.path().join(module))?;
+            std::fs::write(
+                test_dir.path().join(module).join("go.mod"),
+                go_mod(module, requires),
+            )?;
+        }
+
+        let scanned = scan_repo(Some(test_dir.path().into())).await?;
+        let mut index = DependencyIndex::default();
+        index.add_repo(&scanned.repo, &scanned.name)?;
+
+        let target = resolve_target("bitbucket.org/bxbdigital/ledger", &index);
+        let dependents = find_dependents(&[scanned], &index, &target)?;
+
+        let summary: Vec<(&str, bool, Option<&str>, Vec<String>)> = dependents
+            .iter()
+            .map(|dependent| {
+                (
+                    dependent.entity_ref.as_str(),
+                    dependent.direct,
+                    dependent.via.as_deref(),
+                    dependent.versions.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "component:default/payments",
+                    true,
+                    None,
+                    vec!["v1.4.0".to_string()]
+                ),
+                (
+                    "component:default/checkout",
+                    false,
+                    Some("component:default/payments"),
+                    vec!["v0.9.2".to_string()]
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
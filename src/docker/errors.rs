@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AnalyseDockerfilesError {
+    #[error("Failed to compile internal registry regex: {source}")]
+    FailedToCompileInternalRegistryRegex { source: regex::Error },
+
+    #[error("Failed to read the Dockerfile {path}: {source}")]
+    FailedToReadDockerfile {
+        path: String,
+        source: std::io::Error,
+    },
+}
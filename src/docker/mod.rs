@@ -0,0 +1,431 @@
+mod errors;
+
+use crate::backstage::{Component, DependencySource, SourceLocation};
+use crate::config::{DockerConfig, InternalPatterns};
+pub use errors::AnalyseDockerfilesError;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// A base image referenced by a `FROM` instruction in a Dockerfile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseImage {
+    /// The image reference with all build arguments substituted
+    pub image: String,
+
+    /// The name given to the build stage with `AS`, if any
+    pub stage: Option<String>,
+
+    /// The line of the `FROM` instruction, starting from 1
+    pub line: usize,
+}
+
+/// An internal base image and the component that produces it.
+#[derive(Debug, Clone)]
+pub struct InternalImage {
+    pub image: String,
+    pub component: Component,
+}
+
+/// The internal base images used by the Dockerfiles in a project.
+#[derive(Debug, Clone, Default)]
+pub struct DockerfileAnalysis {
+    pub internal_images: Vec<InternalImage>,
+}
+
+impl DockerfileAnalysis {
+    /// Analyses every Dockerfile in the project root and records the internal base images they
+    /// build from.
+    pub fn analyse(
+        project_root: &Path,
+        config: &DockerConfig,
+        internal: &InternalPatterns,
+    ) -> Result<DockerfileAnalysis, AnalyseDockerfilesError> {
+        let internal_registry_regexes = config
+            .internal_registries
+            .iter()
+            .map(|pattern| Regex::new(pattern))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(
+                |source| AnalyseDockerfilesError::FailedToCompileInternalRegistryRegex { source },
+            )?;
+
+        let mut internal_images: Vec<InternalImage> = vec![];
+        for dockerfile_path in find_dockerfiles(project_root) {
+            let dockerfile_text = std::fs::read_to_string(&dockerfile_path).map_err(|source| {
+                AnalyseDockerfilesError::FailedToReadDockerfile {
+                    path: format!("{}", dockerfile_path.display()),
+                    source,
+                }
+            })?;
+
+            for base_image in parse_dockerfile(&dockerfile_text) {
+                if !internal.is_internal_image(&base_image.image)
+                    && !internal_registry_regexes
+                        .iter()
+                        .any(|regex| regex.is_match(&base_image.image))
+                {
+                    continue;
+                }
+
+                if internal_images
+                    .iter()
+                    .any(|internal_image| internal_image.image == base_image.image)
+                {
+                    continue;
+                }
+
+                let reference = ImageReference::parse(&base_image.image);
+                let component = Component::at(
+                    &reference.component_name(&config.images),
+                    Some(SourceLocation {
+                        file: dockerfile_path.clone(),
+                        line: base_image.line,
+                    }),
+                )
+                .with_source(DependencySource::Registry {
+                    name: reference.registry.map(|registry| registry.to_string()),
+                });
+                let component = match reference.tag {
+                    Some(tag) => component.with_version(tag),
+                    None => component,
+                };
+
+                internal_images.push(InternalImage {
+                    component,
+                    image: base_image.image,
+                });
+            }
+        }
+
+        Ok(DockerfileAnalysis { internal_images })
+    }
+
+    /// Returns the components that produce the internal base images.
+    pub fn get_internal_dependencies(&self) -> Vec<Component> {
+        self.internal_images
+            .iter()
+            .map(|internal_image| internal_image.component.clone())
+            .collect()
+    }
+
+    /// Returns the annotations describing the internal base images.
+    pub fn get_annotations(&self) -> BTreeMap<String, String> {
+        let mut annotations = BTreeMap::new();
+        if !self.internal_images.is_empty() {
+            annotations.insert(
+                "metaforge.io/base-images".to_string(),
+                self.internal_images
+                    .iter()
+                    .map(|internal_image| internal_image.image.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+        annotations
+    }
+}
+
+/// Finds the Dockerfiles directly inside the project root, e.g. `Dockerfile`,
+/// `Dockerfile.prod` or `build.Dockerfile`.
+fn find_dockerfiles(project_root: &Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(project_root) else {
+        return vec![];
+    };
+
+    let mut dockerfiles: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    let name = name.to_lowercase();
+                    name == "dockerfile"
+                        || name.starts_with("dockerfile.")
+                        || name.ends_with(".dockerfile")
+                })
+        })
+        .collect();
+    dockerfiles.sort();
+    dockerfiles
+}
+
+/// Parses the `FROM` instructions of a Dockerfile, substituting global build arguments and
+/// skipping stages that build from an earlier stage.
+pub fn parse_dockerfile(dockerfile_text: &str) -> Vec<BaseImage> {
+    let mut build_args: HashMap<String, String> = HashMap::new();
+    let mut stages: Vec<String> = vec![];
+    let mut base_images = vec![];
+    let mut seen_from = false;
+
+    for (line, instruction) in instructions(dockerfile_text) {
+        let mut words = instruction.split_whitespace();
+        let Some(keyword) = words.next() else {
+            continue;
+        };
+
+        match keyword.to_uppercase().as_str() {
+            // only arguments declared before the first FROM can be used in FROM lines
+            "ARG" if !seen_from => {
+                for declaration in words {
+                    let (name, default) = declaration.split_once('=').unwrap_or((declaration, ""));
+                    let default = substitute_build_args(default.trim_matches('"'), &build_args);
+                    build_args.insert(name.to_string(), default);
+                }
+            }
+            "FROM" => {
+                seen_from = true;
+                let arguments: Vec<&str> = words.filter(|word| !word.starts_with("--")).collect();
+                let Some(image) = arguments.first() else {
+                    continue;
+                };
+                let image = substitute_build_args(image, &build_args);
+                let stage = match arguments.as_slice() {
+                    [_, keyword, stage, ..] if keyword.eq_ignore_ascii_case("as") => {
+                        Some(stage.to_string())
+                    }
+                    _ => None,
+                };
+
+                let from_earlier_stage =
+                    stages.iter().any(|name| name.eq_ignore_ascii_case(&image));
+                if let Some(stage) = &stage {
+                    stages.push(stage.clone());
+                }
+                if from_earlier_stage || image == "scratch" {
+                    continue;
+                }
+
+                base_images.push(BaseImage { image, stage, line });
+            }
+            _ => {}
+        }
+    }
+
+    base_images
+}
+
+/// Splits a Dockerfile into instructions and the line each starts on, joining continuation
+/// lines and dropping comments.
+fn instructions(dockerfile_text: &str) -> Vec<(usize, String)> {
+    let mut instructions = vec![];
+    let mut current = String::new();
+    let mut start_line = 1;
+
+    for (line_index, line) in dockerfile_text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if current.trim().is_empty() {
+            start_line = line_index + 1;
+        }
+
+        if let Some(continued) = line.strip_suffix('\\') {
+            current.push_str(continued);
+            current.push(' ');
+            continue;
+        }
+
+        current.push_str(line);
+        if !current.trim().is_empty() {
+            instructions.push((start_line, current.trim().to_string()));
+        }
+        current.clear();
+    }
+
+    if !current.trim().is_empty() {
+        instructions.push((start_line, current.trim().to_string()));
+    }
+
+    instructions
+}
+
+/// Replaces `$NAME`, `${NAME}`, `${NAME:-default}` and `${NAME:+alternative}` with the values
+/// of the build arguments.
+fn substitute_build_args(text: &str, build_args: &HashMap<String, String>) -> String {
+    let Ok(variable_regex) = Regex::new(
+        r#"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::([-+])([^}]*))?\}|\$([A-Za-z_][A-Za-z0-9_]*)"#,
+    ) else {
+        return text.to_string();
+    };
+
+    variable_regex
+        .replace_all(text, |captures: &regex::Captures| {
+            let name = captures
+                .get(1)
+                .or_else(|| captures.get(4))
+                .map_or("", |m| m.as_str());
+            let value = build_args
+                .get(name)
+                .filter(|value| !value.is_empty())
+                .cloned();
+            let modifier_value = captures.get(3).map_or("", |m| m.as_str()).to_string();
+
+            match captures.get(2).map(|m| m.as_str()) {
+                Some("-") => value.unwrap_or(modifier_value),
+                Some("+") => value.map(|_| modifier_value).unwrap_or_default(),
+                _ => value.unwrap_or_default(),
+            }
+        })
+        .to_string()
+}
+
+/// The parts of an image reference, e.g. `registry.bxbdigital.io/platform/golang:1.22`.
+#[derive(Debug, Clone, PartialEq)]
+struct ImageReference<'a> {
+    /// The image without its tag or digest
+    name: &'a str,
+
+    /// The registry host, or `None` for Docker Hub
+    registry: Option<&'a str>,
+
+    /// The repository path within the registry
+    repository_path: &'a str,
+
+    tag: Option<&'a str>,
+}
+
+impl<'a> ImageReference<'a> {
+    fn parse(image: &'a str) -> ImageReference<'a> {
+        // drop the digest and split off the tag
+        let name = image.split('@').next().unwrap_or(image);
+        let (name, tag) = match name.rsplit_once(':') {
+            Some((repository, tag)) if !tag.contains('/') => (repository, Some(tag)),
+            _ => (name, None),
+        };
+
+        // the first segment is a registry host if it looks like one
+        let (registry, repository_path) = match name.split_once('/') {
+            Some((host, path))
+                if host.contains('.') || host.contains(':') || host == "localhost" =>
+            {
+                (Some(host), path)
+            }
+            _ => (None, name),
+        };
+
+        ImageReference {
+            name,
+            registry,
+            repository_path,
+            tag,
+        }
+    }
+
+    /// Works out which component produces the image, using the configured mapping of
+    /// repository paths and falling back to the last segment of the repository path.
+    fn component_name(&self, images: &BTreeMap<String, String>) -> String {
+        images
+            .get(self.name)
+            .or_else(|| images.get(self.repository_path))
+            .cloned()
+            .unwrap_or_else(|| {
+                self.repository_path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(self.repository_path)
+                    .to_string()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    const DOCKERFILE: &str = r#"# syntax=docker/dockerfile:1
+ARG REGISTRY=registry.bxbdigital.io
+ARG GO_VERSION=1.22
+
+FROM --platform=$BUILDPLATFORM ${REGISTRY}/platform/golang-builder:${GO_VERSION} AS build
+ARG REGISTRY=ignored.example.com
+RUN go build \
+    -o /app ./cmd/app
+
+FROM build AS test
+RUN go test ./...
+
+FROM ${REGISTRY}/platform/distroless-base:${BASE_TAG:-latest}
+COPY --from=build /app /app
+"#;
+
+    #[test]
+    fn test_parse_dockerfile() {
+        let base_images = parse_dockerfile(DOCKERFILE);
+
+        assert_eq!(
+            base_images,
+            vec![
+                BaseImage {
+                    image: "registry.bxbdigital.io/platform/golang-builder:1.22".to_string(),
+                    stage: Some("build".to_string()),
+                    line: 5,
+                },
+                BaseImage {
+                    image: "registry.bxbdigital.io/platform/distroless-base:latest".to_string(),
+                    stage: None,
+                    line: 13,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyse() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("Dockerfile"), DOCKERFILE)?;
+        std::fs::write(
+            test_dir.path().join("Dockerfile.debug"),
+            "FROM alpine:3.19\nFROM bxbdigital-fans/toolbox:2\nFROM bxbdigital/toolbox:2\n",
+        )?;
+
+        let config = DockerConfig {
+            images: BTreeMap::from([(
+                "platform/golang-builder".to_string(),
+                "go-build-images".to_string(),
+            )]),
+            ..DockerConfig::default()
+        };
+
+        let analysis = DockerfileAnalysis::analyse(
+            test_dir.path(),
+            &config,
+            &InternalConfig::default().compile()?,
+        )?;
+        let dependencies = analysis.get_internal_dependencies();
+        let summary: Vec<(&str, Option<&str>, Option<&DependencySource>)> = dependencies
+            .iter()
+            .map(|component| {
+                (
+                    component.name.as_str(),
+                    component.version.as_deref(),
+                    component.source.as_ref(),
+                )
+            })
+            .collect();
+
+        let internal_registry = DependencySource::Registry {
+            name: Some("registry.bxbdigital.io".to_string()),
+        };
+        assert_eq!(
+            summary,
+            vec![
+                ("go-build-images", Some("1.22"), Some(&internal_registry)),
+                ("distroless-base", Some("latest"), Some(&internal_registry)),
+                (
+                    "toolbox",
+                    Some("2"),
+                    Some(&DependencySource::Registry { name: None })
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
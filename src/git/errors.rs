@@ -20,6 +20,15 @@ pub enum GetOriginRemoteRepoNameError {
     FailedToFindGitRepoRoot(#[from] GitRepoTryFromError),
 }
 
+#[derive(Error, Debug)]
+pub enum FindDirectoriesError {
+    #[error("Failed to read the directory {path}: {source}")]
+    FailedToReadDirectory {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
 #[derive(Error, Debug)]
 pub enum FindProjectsError {
     #[error("Failed to find to get the file name from: {path}")]
@@ -27,4 +36,16 @@ pub enum FindProjectsError {
 
     #[error("Failed to get project root path for path: {path}")]
     FailedToGetProjectRootPath { path: String },
+
+    #[error("Failed to read the directory {path}: {source}")]
+    FailedToReadDirectory {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to list the repository's directories: {0}")]
+    FindDirectories(#[from] FindDirectoriesError),
+
+    #[error("Failed to finish looking for projects: {source}")]
+    FailedToJoinTask { source: tokio::task::JoinError },
 }
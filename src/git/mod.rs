@@ -1,18 +1,20 @@
 mod errors;
+mod remote;
 // use crate::project::Project;
+use crate::apis::{Api, FindApisError};
+use crate::config::InternalPatterns;
 use crate::languages::Languages;
 use crate::project::Project;
-use async_walkdir::WalkDir;
-pub use errors::FindProjectsError;
+pub use errors::{FindDirectoriesError, FindProjectsError};
 pub use errors::{GetOriginRemoteRepoNameError, GitRepoTryFromError};
-use futures_lite::stream::StreamExt;
-use std::path::PathBuf;
-use strum::IntoEnumIterator;
+pub use remote::{GitHost, GitRemote};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub struct GitRepo {
     pub root: PathBuf,
     pub projects: Vec<Project>,
+    pub apis: Vec<Api>,
 }
 
 impl GitRepo {
@@ -31,6 +33,7 @@ impl GitRepo {
             return Ok(GitRepo {
                 root: cwd,
                 projects,
+                apis: vec![],
             });
         }
 
@@ -47,6 +50,7 @@ impl GitRepo {
                 return Ok(GitRepo {
                     root: current_dir,
                     projects,
+                    apis: vec![],
                 });
             }
         }
@@ -89,60 +93,153 @@ impl GitRepo {
         Ok(repo_name)
     }
 
+    /// Parses the URL of the origin remote, if there is one.
+    pub fn get_origin_remote(&self) -> Option<GitRemote> {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&self.root)
+            .arg("remote")
+            .arg("get-url")
+            .arg("origin")
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        GitRemote::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// The default branch of the origin remote, falling back to the checked out branch and then
+    /// to `main` when it can't be found.
+    pub fn get_default_branch(&self) -> String {
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(&self.root)
+                .args(args)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|branch| !branch.is_empty() && branch != "HEAD")
+        };
+
+        git(&["symbolic-ref", "--short", "refs/remotes/origin/HEAD"])
+            .map(|branch| branch.trim_start_matches("origin/").to_string())
+            .or_else(|| git(&["symbolic-ref", "--short", "HEAD"]))
+            .unwrap_or_else(|| "main".to_string())
+    }
+
     /// Find projects
     pub async fn find_projects(&mut self) -> Result<(), FindProjectsError> {
-        let mut entries = WalkDir::new(&self.root);
+        // walking the repository and reading its files blocks, so keep it off the async workers
+        let root = self.root.clone();
+        let projects = tokio::task::spawn_blocking(move || detect_projects(&root))
+            .await
+            .map_err(|source| FindProjectsError::FailedToJoinTask { source })??;
+        self.projects.extend(projects);
 
-        // iterate over all directories in the git repo root
-        loop {
-            match entries.next().await {
-                Some(Ok(entry)) => {
-                    for language in Languages::iter() {
-                        if entry.path().is_dir() {
-                            continue;
-                        }
-
-                        let path_string = entry
-                            .path()
-                            .file_name()
-                            .ok_or_else(|| FindProjectsError::FailedToGetFileName {
-                                path: format!("{}", entry.path().display()),
-                            })?
-                            .to_str()
-                            .ok_or_else(|| FindProjectsError::FailedToGetFileName {
-                                path: format!("{}", entry.path().display()),
-                            })?
-                            .to_string();
-
-                        if path_string == language.get_project_file_name() {
-                            self.projects.push(Project::new(
-                                entry
-                                    .path()
-                                    .parent()
-                                    .ok_or_else(|| FindProjectsError::FailedToGetProjectRootPath {
-                                        path: format!("{}", entry.path().display()),
-                                    })?
-                                    .to_path_buf(),
-                                language.get_language(),
-                            ));
-                        }
-                    }
-                }
-                Some(Err(err)) => {
-                    eprintln!("error: {err}");
-                    break;
-                }
-                None => break,
+        Ok(())
+    }
+
+    /// Find API definitions, leaving out those in the [`EXCLUDED_DIRECTORIES`]
+    pub async fn find_apis(&mut self, internal: &InternalPatterns) -> Result<(), FindApisError> {
+        let root = self.root.clone();
+        let internal = internal.clone();
+        self.apis = tokio::task::spawn_blocking(move || {
+            let files: Vec<PathBuf> = find_directories(&root)?
+                .into_iter()
+                .filter_map(|directory| std::fs::read_dir(directory).ok())
+                .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .collect();
+            crate::apis::find_apis(&root, &files, &internal)
+        })
+        .await
+        .map_err(|source| FindApisError::FailedToJoinTask { source })??;
+
+        Ok(())
+    }
+
+    /// Get the APIs provided by a project, which are the APIs inside its root that are not
+    /// inside a more deeply nested project.
+    pub fn get_project_apis(&self, project: &Project) -> Vec<&Api> {
+        self.apis
+            .iter()
+            .filter(|api| {
+                self.projects
+                    .iter()
+                    .filter(|candidate| api.root.starts_with(&candidate.root))
+                    .max_by_key(|candidate| candidate.root.components().count())
+                    .is_some_and(|owner| owner.root == project.root)
+            })
+            .collect()
+    }
+}
+
+/// Directories holding git metadata, installed dependencies or build output rather than
+/// projects of the repository.
+pub const EXCLUDED_DIRECTORIES: [&str; 5] = [".git", "node_modules", "target", "vendor", "dist"];
+
+/// Lists `root` and every directory under it, skipping the [`EXCLUDED_DIRECTORIES`]. Symlinks
+/// are not followed, so a link back up the tree can't send the walk round in circles.
+pub fn find_directories(root: &Path) -> Result<Vec<PathBuf>, FindDirectoriesError> {
+    let mut found = vec![];
+    let mut directories = vec![root.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        let failed_to_read = |source| FindDirectoriesError::FailedToReadDirectory {
+            path: format!("{}", directory.display()),
+            source,
+        };
+        for entry in std::fs::read_dir(&directory).map_err(failed_to_read)? {
+            let entry = entry.map_err(failed_to_read)?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if entry.file_type().map_err(failed_to_read)?.is_dir()
+                && !EXCLUDED_DIRECTORIES.contains(&file_name.as_str())
+            {
+                directories.push(entry.path());
             }
         }
+        found.push(directory);
+    }
 
-        Ok(())
+    found.sort();
+    Ok(found)
+}
+
+/// Asks each language whether each directory of the repository is the root of one of its
+/// projects.
+fn detect_projects(root: &Path) -> Result<Vec<Project>, FindProjectsError> {
+    let mut projects = vec![];
+    for directory in find_directories(root)? {
+        let mut file_names: Vec<String> = std::fs::read_dir(&directory)
+            .map_err(|source| FindProjectsError::FailedToReadDirectory {
+                path: format!("{}", directory.display()),
+                source,
+            })?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_string()))
+            .collect();
+        file_names.sort();
+
+        // a directory with several languages is still one project
+        let languages = Languages::detect_languages(&directory, &file_names);
+        if !languages.is_empty() {
+            projects.push(Project::new(directory, languages));
+        }
     }
+
+    Ok(projects)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::InternalConfig;
     use anyhow::{Ok, Result};
     use tempfile::tempdir;
 
@@ -234,4 +331,123 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_find_projects() -> Result<()> {
+        // make a temporary git repository
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+
+        // a cargo workspace root is not a project, but its member crates are
+        std::fs::write(
+            test_dir.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"api\"]\n",
+        )?;
+        std::fs::create_dir(test_dir.path().join("api"))?;
+        std::fs::write(
+            test_dir.path().join("api").join("Cargo.toml"),
+            "[package]\nname = \"api\"\n",
+        )?;
+
+        // the kustomization adds a second, less certain, language to the crate
+        std::fs::write(
+            test_dir.path().join("api").join("kustomization.yaml"),
+            "resources: []\n",
+        )?;
+
+        std::fs::create_dir(test_dir.path().join("worker"))?;
+        std::fs::write(
+            test_dir.path().join("worker").join("go.mod"),
+            "module worker\n",
+        )?;
+
+        // vendored modules and build output are not projects of the repository
+        for directory in [
+            "worker/vendor/example.com/lib",
+            "target/package/api-0.1.0",
+            "dist",
+        ] {
+            std::fs::create_dir_all(test_dir.path().join(directory))?;
+            std::fs::write(
+                test_dir.path().join(directory).join("go.mod"),
+                "module example.com/lib\n",
+            )?;
+        }
+
+        let mut repo = GitRepo::try_from(Some(test_dir.path().into())).await?;
+        repo.find_projects().await?;
+
+        let mut projects: Vec<(String, Vec<String>)> = repo
+            .projects
+            .iter()
+            .map(|project| {
+                (
+                    project
+                        .root
+                        .strip_prefix(test_dir.path())
+                        .unwrap_or(&project.root)
+                        .display()
+                        .to_string(),
+                    project.get_language_tags(),
+                )
+            })
+            .collect();
+        projects.sort();
+
+        assert_eq!(
+            projects,
+            vec![
+                (
+                    "api".to_string(),
+                    vec!["rust".to_string(), "kubernetes".to_string()]
+                ),
+                ("worker".to_string(), vec!["go".to_string()])
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_find_projects_symlink_loop() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+        std::fs::create_dir(test_dir.path().join("a"))?;
+        std::fs::write(test_dir.path().join("a").join("go.mod"), "module a\n")?;
+        std::os::unix::fs::symlink("..", test_dir.path().join("a").join("loop"))?;
+
+        let directories = find_directories(test_dir.path())?;
+        assert_eq!(
+            directories,
+            vec![test_dir.path().to_path_buf(), test_dir.path().join("a")]
+        );
+
+        let mut repo = GitRepo::try_from(Some(test_dir.path().into())).await?;
+        repo.find_projects().await?;
+        assert_eq!(repo.projects.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_apis() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+
+        let spec = "openapi: 3.0.3\ninfo:\n  title: Orders\npaths: {}\n";
+        for directory in ["api", "node_modules/some-package", "vendor/example.com/lib"] {
+            std::fs::create_dir_all(test_dir.path().join(directory))?;
+            std::fs::write(test_dir.path().join(directory).join("openapi.yaml"), spec)?;
+        }
+
+        let mut repo = GitRepo::try_from(Some(test_dir.path().into())).await?;
+        repo.find_apis(&InternalConfig::default().compile()?)
+            .await?;
+
+        assert_eq!(repo.apis.len(), 1);
+        assert_eq!(repo.apis[0].root, test_dir.path().join("api"));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The source control hosts we know how to link to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GitHost {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl GitHost {
+    /// The Backstage icon for links to the host.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            GitHost::GitHub => "github",
+            GitHost::GitLab => "gitlab",
+            GitHost::Bitbucket => "bitbucket",
+        }
+    }
+}
+
+/// A parsed git remote, e.g. `git@bitbucket.org:bxbdigital/payments.git`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitRemote {
+    /// The host name, e.g. `bitbucket.org`
+    pub host: String,
+
+    /// The path of the repository on the host, e.g. `bxbdigital/payments`
+    pub slug: String,
+}
+
+impl GitRemote {
+    /// Parses SSH (`git@host:owner/repo.git`, `ssh://git@host:22/owner/repo.git`) and HTTP
+    /// (`https://user@host/owner/repo.git`) remote URLs.
+    pub fn parse(url: &str) -> Option<GitRemote> {
+        let url = url.trim();
+        let (host, path) = match url.split_once("://") {
+            Some((_, rest)) => {
+                let (authority, path) = rest.split_once('/')?;
+                let host = authority.rsplit('@').next()?;
+                (host.split(':').next()?, path)
+            }
+            None => {
+                let (authority, path) = url.split_once(':')?;
+                (authority.rsplit('@').next()?, path)
+            }
+        };
+
+        let slug = path
+            .trim_matches('/')
+            .trim_end_matches(".git")
+            .trim_start_matches("scm/")
+            .to_string();
+        if host.is_empty() || !slug.contains('/') {
+            return None;
+        }
+
+        Some(GitRemote {
+            host: host.to_lowercase(),
+            slug,
+        })
+    }
+
+    /// The kind of host the remote is on, if we know it.
+    pub fn git_host(&self) -> Option<GitHost> {
+        if self.host.contains("github") {
+            Some(GitHost::GitHub)
+        } else if self.host.contains("gitlab") {
+            Some(GitHost::GitLab)
+        } else if self.host.contains("bitbucket") {
+            Some(GitHost::Bitbucket)
+        } else {
+            None
+        }
+    }
+
+    /// The web URL of a directory or file in the repository on `branch`.
+    pub fn browse_url(&self, branch: &str, relative_path: &Path, is_dir: bool) -> Option<String> {
+        let base = format!("https://{}/{}", self.host, self.slug);
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+        let mut path = if relative_path.is_empty() {
+            String::new()
+        } else {
+            format!("/{relative_path}")
+        };
+        if is_dir {
+            path.push('/');
+        }
+
+        match self.git_host()? {
+            GitHost::GitHub if is_dir => Some(format!("{base}/tree/{branch}{path}")),
+            GitHost::GitHub => Some(format!("{base}/blob/{branch}{path}")),
+            GitHost::GitLab if is_dir => Some(format!("{base}/-/tree/{branch}{path}")),
+            GitHost::GitLab => Some(format!("{base}/-/blob/{branch}{path}")),
+            GitHost::Bitbucket => Some(format!("{base}/src/{branch}{path}")),
+        }
+    }
+
+    /// The standard Backstage source control annotations for the project at `relative_dir`,
+    /// whose catalog info file is at `catalog_path`. Both paths are relative to the repository
+    /// root.
+    pub fn annotations(
+        &self,
+        branch: &str,
+        relative_dir: &Path,
+        catalog_path: &Path,
+    ) -> BTreeMap<String, String> {
+        let mut annotations = BTreeMap::new();
+
+        let slug_annotation = match self.git_host() {
+            Some(GitHost::GitHub) => "github.com/project-slug",
+            Some(GitHost::GitLab) => "gitlab.com/project-slug",
+            Some(GitHost::Bitbucket) => "bitbucket.org/project-slug",
+            None => return annotations,
+        };
+        annotations.insert(slug_annotation.to_string(), self.slug.clone());
+
+        if let Some(url) = self.browse_url(branch, relative_dir, true) {
+            annotations.insert(
+                "backstage.io/source-location".to_string(),
+                format!("url:{url}"),
+            );
+        }
+        if let Some(url) = self.browse_url(branch, catalog_path, false) {
+            annotations.insert(
+                "backstage.io/managed-by-location".to_string(),
+                format!("url:{url}"),
+            );
+        }
+
+        annotations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let expected = Some(GitRemote {
+            host: "bitbucket.org".to_string(),
+            slug: "bxbdigital/payments".to_string(),
+        });
+
+        assert_eq!(
+            GitRemote::parse("git@bitbucket.org:bxbdigital/payments.git"),
+            expected
+        );
+        assert_eq!(
+            GitRemote::parse("ssh://git@bitbucket.org:22/bxbdigital/payments.git"),
+            expected
+        );
+        assert_eq!(
+            GitRemote::parse("https://someone@bitbucket.org/bxbdigital/payments.git\n"),
+            expected
+        );
+        assert_eq!(GitRemote::parse("/srv/git/payments"), None);
+    }
+
+    #[test]
+    fn test_annotations() {
+        let remote = GitRemote::parse("git@github.com:bxbdigital/platform.git")
+            .expect("the remote to parse");
+
+        let annotations = remote.annotations(
+            "main",
+            Path::new("services/payments"),
+            Path::new("services/payments/catalog-info.yaml"),
+        );
+
+        assert_eq!(
+            annotations,
+            BTreeMap::from([
+                (
+                    "backstage.io/managed-by-location".to_string(),
+                    "url:https://github.com/bxbdigital/platform/blob/main/services/payments/catalog-info.yaml"
+                        .to_string()
+                ),
+                (
+                    "backstage.io/source-location".to_string(),
+                    "url:https://github.com/bxbdigital/platform/tree/main/services/payments/"
+                        .to_string()
+                ),
+                (
+                    "github.com/project-slug".to_string(),
+                    "bxbdigital/platform".to_string()
+                ),
+            ])
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use crate::project::GetProjectDependenciesError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BuildGraphError {
+    #[error("Failed to get a project's internal dependencies: {0}")]
+    GetInternalDependencies(#[from] GetProjectDependenciesError),
+}
+
+#[derive(Error, Debug)]
+pub enum RenderGraphError {
+    #[error("Failed to serialise the graph to JSON: {source}")]
+    FailedToSerialiseJson { source: serde_json::Error },
+}
@@ -0,0 +1,596 @@
+mod errors;
+
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::index::DependencyIndex;
+use crate::scan::ScannedRepo;
+pub use errors::{BuildGraphError, RenderGraphError};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A component in the dependency graph.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct GraphNode {
+    /// The entity ref of the component, e.g. `component:default/payments`
+    pub id: String,
+
+    pub label: String,
+
+    /// The repository the component lives in, if it was scanned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+
+    /// Whether the component is only known as a dependency of a scanned project
+    pub external: bool,
+}
+
+/// The projects of one or more repositories and the dependencies between them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DependencyGraph {
+    pub nodes: BTreeMap<String, GraphNode>,
+    pub edges: BTreeSet<(String, String)>,
+}
+
+/// The formats a dependency graph can be rendered in.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum GraphFormat {
+    /// A Graphviz digraph
+    Dot,
+
+    /// A Mermaid flowchart
+    Mermaid,
+
+    /// A JSON adjacency list
+    Json,
+}
+
+/// The JSON rendering of a graph.
+#[derive(Serialize, Debug)]
+struct JsonGraph<'a> {
+    nodes: Vec<&'a GraphNode>,
+    adjacency: BTreeMap<&'a str, Vec<&'a str>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cycles: Option<Vec<Vec<String>>>,
+}
+
+impl DependencyGraph {
+    /// Builds the graph of the repository's projects and their internal dependencies.
+    /// Dependencies on components that aren't projects in the repository become external nodes.
+    pub fn for_repo(
+        repo: &GitRepo,
+        repo_name: &str,
+        config: &Config,
+        index: &DependencyIndex,
+    ) -> Result<DependencyGraph, BuildGraphError> {
+        let mut graph = DependencyGraph::default();
+
+        for project in &repo.projects {
+            graph.add_node(GraphNode {
+                id: project.get_entity_ref(&repo.root, repo_name),
+                label: project.get_name(&repo.root, repo_name),
+                repo: Some(repo_name.to_string()),
+                external: false,
+            });
+        }
+
+        for project in &repo.projects {
+            let self_ref = project.get_entity_ref(&repo.root, repo_name);
+            let dependencies = project.get_internal_dependencies(&self_ref, config, index)?;
+
+            for dependency in dependencies
+                .into_iter()
+                .filter(|dependency| !dependency.indirect)
+            {
+                graph.add_external_node(&dependency.entity_ref);
+                graph.add_edge(&self_ref, &dependency.entity_ref);
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Builds the graph of the projects of every scanned repository, so dependencies between
+    /// the repositories become edges between their projects.
+    pub fn for_repos(
+        repos: &[ScannedRepo],
+        index: &DependencyIndex,
+    ) -> Result<DependencyGraph, BuildGraphError> {
+        let mut graph = DependencyGraph::default();
+        for scanned in repos {
+            graph.merge(DependencyGraph::for_repo(
+                &scanned.repo,
+                &scanned.name,
+                &scanned.config,
+                index,
+            )?);
+        }
+
+        Ok(graph)
+    }
+
+    /// Adds a node, replacing an external node with the same entity ref.
+    pub fn add_node(&mut self, node: GraphNode) {
+        match self.nodes.get(&node.id) {
+            Some(existing) if !existing.external => {}
+            _ => {
+                self.nodes.insert(node.id.clone(), node);
+            }
+        }
+    }
+
+    /// Adds a node for a dependency outside the scanned projects, unless it is already known.
+    pub fn add_external_node(&mut self, entity_ref: &str) {
+        if !self.nodes.contains_key(entity_ref) {
+            let label = entity_ref.rsplit('/').next().unwrap_or(entity_ref);
+            self.nodes.insert(
+                entity_ref.to_string(),
+                GraphNode {
+                    id: entity_ref.to_string(),
+                    label: label.to_string(),
+                    repo: None,
+                    external: true,
+                },
+            );
+        }
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str) {
+        self.edges.insert((from.to_string(), to.to_string()));
+    }
+
+    /// Merges another graph into this one, e.g. to combine the graphs of several repositories.
+    pub fn merge(&mut self, other: DependencyGraph) {
+        for node in other.nodes.into_values() {
+            self.add_node(node);
+        }
+        self.edges.extend(other.edges);
+    }
+
+    /// The graph without the external nodes and the edges to them.
+    pub fn without_external(&self) -> DependencyGraph {
+        let nodes: BTreeMap<String, GraphNode> = self
+            .nodes
+            .iter()
+            .filter(|(_, node)| !node.external)
+            .map(|(id, node)| (id.clone(), node.clone()))
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .filter(|(from, to)| nodes.contains_key(from) && nodes.contains_key(to))
+            .cloned()
+            .collect();
+
+        DependencyGraph { nodes, edges }
+    }
+
+    /// The graph with the projects of each repository merged into a single node. Edges within
+    /// a repository are dropped.
+    pub fn collapse_by_repo(&self) -> DependencyGraph {
+        let collapsed_id = |id: &str| match self.nodes.get(id).and_then(|node| node.repo.as_ref()) {
+            Some(repo) => format!("repo:{repo}"),
+            None => id.to_string(),
+        };
+
+        let mut graph = DependencyGraph::default();
+        for node in self.nodes.values() {
+            match &node.repo {
+                Some(repo) => graph.add_node(GraphNode {
+                    id: format!("repo:{repo}"),
+                    label: repo.clone(),
+                    repo: Some(repo.clone()),
+                    external: false,
+                }),
+                None => graph.add_node(node.clone()),
+            }
+        }
+        for (from, to) in &self.edges {
+            let (from, to) = (collapsed_id(from), collapsed_id(to));
+            if from != to {
+                graph.add_edge(&from, &to);
+            }
+        }
+
+        graph
+    }
+
+    /// The entity refs of `id`'s direct dependencies.
+    pub fn dependencies_of(&self, id: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter(|(from, _)| from == id)
+            .map(|(_, to)| to.as_str())
+            .collect()
+    }
+
+    /// Finds the dependency cycles in the graph: the strongly connected components with more
+    /// than one node, and nodes depending on themselves. Each cycle is sorted.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            index: 0,
+            indices: BTreeMap::new(),
+            low_links: BTreeMap::new(),
+            stack: vec![],
+            on_stack: BTreeSet::new(),
+            components: vec![],
+        };
+        for id in self.nodes.keys() {
+            if !tarjan.indices.contains_key(id.as_str()) {
+                tarjan.visit(id);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = tarjan
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || self
+                        .edges
+                        .contains(&(component[0].to_string(), component[0].to_string()))
+            })
+            .map(|component| {
+                let mut cycle: Vec<String> =
+                    component.into_iter().map(|id| id.to_string()).collect();
+                cycle.sort();
+                cycle
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    /// A path around the `cycle` found by [`DependencyGraph::cycles`], starting and ending at
+    /// its first node, e.g. `[a, b, c, a]`. The path is the shortest one back to the first
+    /// node, so it may not visit every node of the cycle.
+    pub fn cycle_path(&self, cycle: &[String]) -> Vec<String> {
+        let Some(start) = cycle.first() else {
+            return vec![];
+        };
+
+        let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut queue = std::collections::VecDeque::from([start.as_str()]);
+        while let Some(id) = queue.pop_front() {
+            for dependency in self.dependencies_of(id) {
+                if !cycle.iter().any(|node| node == dependency) {
+                    continue;
+                }
+                if dependency == start {
+                    let mut path = vec![start.clone(), id.to_string()];
+                    let mut current = id;
+                    while let Some(&before) = previous.get(current) {
+                        path.push(before.to_string());
+                        current = before;
+                    }
+                    path.reverse();
+                    return path;
+                }
+                if !previous.contains_key(dependency) {
+                    previous.insert(dependency, id);
+                    queue.push_back(dependency);
+                }
+            }
+        }
+
+        vec![]
+    }
+
+    /// The edges that are part of a dependency cycle.
+    fn cycle_edges(&self) -> BTreeSet<(String, String)> {
+        let cycles = self.cycles();
+        self.edges
+            .iter()
+            .filter(|(from, to)| {
+                cycles
+                    .iter()
+                    .any(|cycle| cycle.contains(from) && cycle.contains(to))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Renders the graph in `format`, colouring the nodes and edges of cycles red when
+    /// `highlight_cycles` is set.
+    pub fn render(
+        &self,
+        format: GraphFormat,
+        highlight_cycles: bool,
+    ) -> Result<String, RenderGraphError> {
+        let cycle_edges = if highlight_cycles {
+            self.cycle_edges()
+        } else {
+            BTreeSet::new()
+        };
+        let in_cycle = |id: &str| cycle_edges.iter().any(|(from, to)| from == id || to == id);
+
+        match format {
+            GraphFormat::Dot => {
+                let mut dot = String::from("digraph dependencies {\n  rankdir=LR;\n");
+                for node in self.nodes.values() {
+                    let mut attributes = vec![format!("label=\"{}\"", node.label)];
+                    if node.external {
+                        attributes.push("style=dashed".to_string());
+                    }
+                    if in_cycle(&node.id) {
+                        attributes.push("color=red".to_string());
+                    }
+                    dot.push_str(&format!("  \"{}\" [{}];\n", node.id, attributes.join(", ")));
+                }
+                for edge in &self.edges {
+                    let colour = if cycle_edges.contains(edge) {
+                        " [color=red]"
+                    } else {
+                        ""
+                    };
+                    dot.push_str(&format!("  \"{}\" -> \"{}\"{colour};\n", edge.0, edge.1));
+                }
+                dot.push_str("}\n");
+                Ok(dot)
+            }
+            GraphFormat::Mermaid => {
+                // mermaid ids can't contain the `:` and `/` of entity refs
+                let ids: BTreeMap<&str, String> = self
+                    .nodes
+                    .keys()
+                    .enumerate()
+                    .map(|(index, id)| (id.as_str(), format!("n{index}")))
+                    .collect();
+
+                let mut mermaid = String::from("flowchart LR\n");
+                for node in self.nodes.values() {
+                    mermaid.push_str(&format!(
+                        "  {}[\"{}\"]\n",
+                        ids[node.id.as_str()],
+                        node.label
+                    ));
+                }
+                let mut cycle_links = vec![];
+                for (index, edge) in self.edges.iter().enumerate() {
+                    mermaid.push_str(&format!(
+                        "  {} --> {}\n",
+                        ids[edge.0.as_str()],
+                        ids[edge.1.as_str()]
+                    ));
+                    if cycle_edges.contains(edge) {
+                        cycle_links.push(index.to_string());
+                    }
+                }
+
+                let external: Vec<&str> = self
+                    .nodes
+                    .values()
+                    .filter(|node| node.external)
+                    .map(|node| ids[node.id.as_str()].as_str())
+                    .collect();
+                if !external.is_empty() {
+                    mermaid.push_str("  classDef external stroke-dasharray: 5 5\n");
+                    mermaid.push_str(&format!("  class {} external\n", external.join(",")));
+                }
+                if !cycle_links.is_empty() {
+                    let cycle_nodes: Vec<&str> = self
+                        .nodes
+                        .keys()
+                        .filter(|id| in_cycle(id))
+                        .map(|id| ids[id.as_str()].as_str())
+                        .collect();
+                    mermaid.push_str("  classDef cycle stroke:red\n");
+                    mermaid.push_str(&format!("  class {} cycle\n", cycle_nodes.join(",")));
+                    mermaid.push_str(&format!(
+                        "  linkStyle {} stroke:red\n",
+                        cycle_links.join(",")
+                    ));
+                }
+                Ok(mermaid)
+            }
+            GraphFormat::Json => {
+                let json_graph = JsonGraph {
+                    nodes: self.nodes.values().collect(),
+                    adjacency: self
+                        .nodes
+                        .keys()
+                        .map(|id| (id.as_str(), self.dependencies_of(id)))
+                        .collect(),
+                    cycles: highlight_cycles.then(|| self.cycles()),
+                };
+                serde_json::to_string_pretty(&json_graph)
+                    .map_err(|source| RenderGraphError::FailedToSerialiseJson { source })
+            }
+        }
+    }
+}
+
+/// Tarjan's strongly connected components algorithm.
+struct Tarjan<'a> {
+    graph: &'a DependencyGraph,
+    index: usize,
+    indices: BTreeMap<&'a str, usize>,
+    low_links: BTreeMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn visit(&mut self, id: &'a str) {
+        self.indices.insert(id, self.index);
+        self.low_links.insert(id, self.index);
+        self.index += 1;
+        self.stack.push(id);
+        self.on_stack.insert(id);
+
+        for dependency in self.graph.dependencies_of(id) {
+            if !self.indices.contains_key(dependency) {
+                self.visit(dependency);
+                let low_link = self.low_links[id].min(self.low_links[dependency]);
+                self.low_links.insert(id, low_link);
+            } else if self.on_stack.contains(dependency) {
+                let low_link = self.low_links[id].min(self.indices[dependency]);
+                self.low_links.insert(id, low_link);
+            }
+        }
+
+        if self.low_links[id] == self.indices[id] {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == id {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{build_index, scan_repo};
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        for (name, repo) in [("api", "shop"), ("worker", "shop"), ("ledger", "finance")] {
+            graph.add_node(GraphNode {
+                id: format!("component:default/{name}"),
+                label: name.to_string(),
+                repo: Some(repo.to_string()),
+                external: false,
+            });
+        }
+        graph.add_external_node("component:default/auth");
+
+        graph.add_edge("component:default/api", "component:default/worker");
+        graph.add_edge("component:default/worker", "component:default/ledger");
+        graph.add_edge("component:default/ledger", "component:default/api");
+        graph.add_edge("component:default/api", "component:default/auth");
+        graph
+    }
+
+    #[test]
+    fn test_cycles() {
+        assert_eq!(
+            graph().cycles(),
+            vec![vec![
+                "component:default/api".to_string(),
+                "component:default/ledger".to_string(),
+                "component:default/worker".to_string(),
+            ]]
+        );
+        assert!(graph().collapse_by_repo().without_external().cycles().len() == 1);
+    }
+
+    #[test]
+    fn test_cycle_path() {
+        let mut graph = graph();
+        let cycle = graph.cycles().remove(0);
+        assert_eq!(
+            graph.cycle_path(&cycle),
+            vec![
+                "component:default/api",
+                "component:default/worker",
+                "component:default/ledger",
+                "component:default/api",
+            ]
+        );
+
+        graph.add_edge("component:default/auth", "component:default/auth");
+        assert_eq!(
+            graph.cycle_path(&["component:default/auth".to_string()]),
+            vec!["component:default/auth", "component:default/auth"]
+        );
+    }
+
+    #[test]
+    fn test_collapse_by_repo() {
+        let graph = graph().collapse_by_repo();
+
+        assert_eq!(
+            graph.nodes.keys().collect::<Vec<_>>(),
+            vec!["component:default/auth", "repo:finance", "repo:shop"]
+        );
+        assert_eq!(
+            graph.edges,
+            BTreeSet::from([
+                ("repo:finance".to_string(), "repo:shop".to_string()),
+                (
+                    "repo:shop".to_string(),
+                    "component:default/auth".to_string()
+                ),
+                ("repo:shop".to_string(), "repo:finance".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let graph = graph().without_external();
+
+        let dot = graph.render(GraphFormat::Dot, true)?;
+        assert!(
+            dot.contains("\"component:default/api\" -> \"component:default/worker\" [color=red];")
+        );
+        assert!(!dot.contains("auth"));
+
+        let mermaid = graph.render(GraphFormat::Mermaid, false)?;
+        assert_eq!(
+            mermaid,
+            "flowchart LR\n  n0[\"api\"]\n  n1[\"ledger\"]\n  n2[\"worker\"]\n  n0 --> n2\n  n1 --> n0\n  n2 --> n1\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&graph.render(GraphFormat::Json, true)?)?;
+        assert_eq!(
+            json["adjacency"]["component:default/api"],
+            serde_json::json!(["component:default/worker"])
+        );
+        assert_eq!(
+            json["cycles"][0].as_array().map(|cycle| cycle.len()),
+            Some(3)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_repos() -> Result<()> {
+        let test_dir = tempdir()?;
+        for (repo, module, require) in [
+            (
+                "shop",
+                "orders",
+                "\tbitbucket.org/bxbdigital/ledger v1.0.0\n",
+            ),
+            ("finance", "ledger", ""),
+        ] {
+            let project_root = test_dir.path().join(repo).join(module);
+            std::fs::create_dir_all(&project_root)?;
+            std::fs::create_dir(test_dir.path().join(repo).join(".git"))?;
+            std::fs::write(
+                project_root.join("go.mod"),
+                format!("module bitbucket.org/bxbdigital/{module}\n\nrequire (\n{require})\n"),
+            )?;
+        }
+
+        let repos = vec![
+            scan_repo(Some(test_dir.path().join("shop"))).await?,
+            scan_repo(Some(test_dir.path().join("finance"))).await?,
+        ];
+        let graph = DependencyGraph::for_repos(&repos, &build_index(&repos)?)?;
+
+        assert_eq!(
+            graph.nodes["component:default/ledger"].repo,
+            Some("finance".to_string())
+        );
+        assert_eq!(
+            graph.collapse_by_repo().edges,
+            BTreeSet::from([("repo:shop".to_string(), "repo:finance".to_string())])
+        );
+
+        Ok(())
+    }
+}
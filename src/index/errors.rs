@@ -0,0 +1,49 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LoadIndexError {
+    #[error("Failed to read the dependency index {path}: {source}")]
+    FailedToReadIndexFile {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the dependency index {path}: {source}")]
+    FailedToParseIndexFile {
+        path: String,
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum SaveIndexError {
+    #[error("Failed to serialise the dependency index: {source}")]
+    FailedToSerialiseIndex { source: serde_json::Error },
+
+    #[error("Failed to write the dependency index {path}: {source}")]
+    FailedToWriteIndexFile {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum IndexCatalogError {
+    #[error("Failed to read the catalog file {path}: {source}")]
+    FailedToReadCatalogFile {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the catalog info file {path}: {source}")]
+    FailedToParseCatalogInfoFile {
+        path: String,
+        source: serde_yml::Error,
+    },
+
+    #[error("Failed to parse the catalog export {path}: {source}")]
+    FailedToParseCatalogExport {
+        path: String,
+        source: serde_json::Error,
+    },
+}
@@ -0,0 +1,292 @@
+mod errors;
+
+use crate::backstage::{CATALOG_INFO_FILE, Component};
+use crate::git::GitRepo;
+use crate::languages::errors::GetMetadataError;
+pub use errors::{IndexCatalogError, LoadIndexError, SaveIndexError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The annotation listing the package names a component is published under.
+pub const PACKAGES_ANNOTATION: &str = "metaforge.io/packages";
+
+/// Maps the names projects are published under, e.g. Go module paths, crate names, npm
+/// packages and Maven coordinates, to the entity refs of the components that own them.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct DependencyIndex {
+    pub entries: BTreeMap<String, String>,
+}
+
+/// The parts of a catalog entity that identify the packages it owns.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct CatalogEntity {
+    kind: String,
+    metadata: CatalogEntityMetadata,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct CatalogEntityMetadata {
+    name: String,
+    namespace: Option<String>,
+    annotations: BTreeMap<String, String>,
+}
+
+/// A Backstage catalog export, either the plain list returned by `/api/catalog/entities` or
+/// the paginated `/api/catalog/entities/by-query` response.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum CatalogExport {
+    Entities(Vec<CatalogEntity>),
+    Page { items: Vec<CatalogEntity> },
+}
+
+impl DependencyIndex {
+    pub fn insert(&mut self, package_name: &str, entity_ref: &str) {
+        self.entries
+            .insert(package_name.to_string(), entity_ref.to_string());
+    }
+
+    /// Loads a persisted index, which is empty if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<DependencyIndex, LoadIndexError> {
+        if !path.exists() {
+            return Ok(DependencyIndex::default());
+        }
+
+        let index_text = std::fs::read_to_string(path).map_err(|source| {
+            LoadIndexError::FailedToReadIndexFile {
+                path: format!("{}", path.display()),
+                source,
+            }
+        })?;
+        serde_json::from_str(&index_text).map_err(|source| LoadIndexError::FailedToParseIndexFile {
+            path: format!("{}", path.display()),
+            source,
+        })
+    }
+
+    /// Persists the index so later runs can resolve dependencies on repositories they don't
+    /// scan.
+    pub fn save(&self, path: &Path) -> Result<(), SaveIndexError> {
+        let index_text = serde_json::to_string_pretty(self)
+            .map_err(|source| SaveIndexError::FailedToSerialiseIndex { source })?;
+        std::fs::write(path, index_text).map_err(|source| SaveIndexError::FailedToWriteIndexFile {
+            path: format!("{}", path.display()),
+            source,
+        })
+    }
+
+    /// Adds the entries of another index, which take precedence over existing ones.
+    pub fn merge(&mut self, other: DependencyIndex) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Indexes the components in a catalog info file, or in every catalog info file under a
+    /// directory.
+    pub fn add_catalog_files(&mut self, path: &Path) -> Result<(), IndexCatalogError> {
+        for catalog_path in find_catalog_files(path) {
+            let catalog_text = read_catalog_file(&catalog_path)?;
+            for document in serde_yml::Deserializer::from_str(&catalog_text) {
+                let entity = CatalogEntity::deserialize(document).map_err(|source| {
+                    IndexCatalogError::FailedToParseCatalogInfoFile {
+                        path: format!("{}", catalog_path.display()),
+                        source,
+                    }
+                })?;
+                self.add_entity(&entity);
+            }
+        }
+        Ok(())
+    }
+
+    /// Indexes the components in a JSON export of a Backstage catalog.
+    pub fn add_catalog_export(&mut self, path: &Path) -> Result<(), IndexCatalogError> {
+        let export_text = read_catalog_file(path)?;
+        let export: CatalogExport = serde_json::from_str(&export_text).map_err(|source| {
+            IndexCatalogError::FailedToParseCatalogExport {
+                path: format!("{}", path.display()),
+                source,
+            }
+        })?;
+
+        let (CatalogExport::Entities(entities) | CatalogExport::Page { items: entities }) = export;
+        for entity in &entities {
+            self.add_entity(entity);
+        }
+        Ok(())
+    }
+
+    /// Indexes a component under its name and the packages in its packages annotation.
+    fn add_entity(&mut self, entity: &CatalogEntity) {
+        if !entity.kind.eq_ignore_ascii_case("component") || entity.metadata.name.is_empty() {
+            return;
+        }
+
+        let entity_ref = format!(
+            "component:{}/{}",
+            entity.metadata.namespace.as_deref().unwrap_or("default"),
+            entity.metadata.name
+        );
+        self.insert(&entity.metadata.name, &entity_ref);
+        for package_name in entity
+            .metadata
+            .annotations
+            .get(PACKAGES_ANNOTATION)
+            .into_iter()
+            .flat_map(|packages| packages.split(','))
+            .map(|package_name| package_name.trim())
+            .filter(|package_name| !package_name.is_empty())
+        {
+            self.insert(package_name, &entity_ref);
+        }
+    }
+
+    /// Indexes the package names of every project in the repository.
+    pub fn add_repo(&mut self, repo: &GitRepo, repo_name: &str) -> Result<(), GetMetadataError> {
+        for project in &repo.projects {
+            let entity_ref = project.get_entity_ref(&repo.root, repo_name);
+            for package_name in project.get_package_names()? {
+                self.insert(&package_name, &entity_ref);
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks up the entity owning a package, ignoring a Go style major version suffix, e.g.
+    /// `bitbucket.org/bxbdigital/ledger/v3`.
+    pub fn get(&self, package_name: &str) -> Option<&String> {
+        self.entries.get(package_name).or_else(|| {
+            let (base, suffix) = package_name.rsplit_once('/')?;
+            let is_major_version = suffix.len() > 1
+                && suffix.starts_with('v')
+                && suffix[1..].chars().all(|c| c.is_ascii_digit());
+            is_major_version.then(|| self.entries.get(base)).flatten()
+        })
+    }
+
+    /// The entity ref of a dependency: the indexed owner of the package if there is one, or
+    /// else the entity ref guessed from its name.
+    pub fn resolve(&self, component: &Component) -> String {
+        self.get(&component.name)
+            .cloned()
+            .unwrap_or_else(|| component.entity_ref())
+    }
+}
+
+fn read_catalog_file(path: &Path) -> Result<String, IndexCatalogError> {
+    std::fs::read_to_string(path).map_err(|source| IndexCatalogError::FailedToReadCatalogFile {
+        path: format!("{}", path.display()),
+        source,
+    })
+}
+
+/// Finds the catalog info files at `path`, which is either a file or a directory to search.
+fn find_catalog_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+
+    let mut catalog_files = vec![];
+    let mut directories = vec![path.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                if !file_name.starts_with('.') && file_name != "node_modules" {
+                    directories.push(path);
+                }
+            } else if file_name == CATALOG_INFO_FILE || file_name == "catalog-info.yml" {
+                catalog_files.push(path);
+            }
+        }
+    }
+
+    catalog_files.sort();
+    catalog_files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_add_catalogs() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join("ledger"))?;
+        std::fs::write(
+            test_dir.path().join("ledger").join(CATALOG_INFO_FILE),
+            r#"---
+apiVersion: backstage.io/v1alpha1
+kind: Location
+metadata:
+  name: platform
+spec:
+  targets: []
+---
+apiVersion: backstage.io/v1alpha1
+kind: Component
+metadata:
+  name: ledger
+  annotations:
+    metaforge.io/packages: bitbucket.org/bxbdigital/ledger-go, ledger-client
+spec:
+  type: service
+"#,
+        )?;
+        std::fs::write(
+            test_dir.path().join("export.json"),
+            r#"{"items": [{"kind": "Component", "metadata": {"name": "checkout", "namespace": "shop", "annotations": {"metaforge.io/packages": "@bxbdigital/checkout"}}}]}"#,
+        )?;
+
+        let mut index = DependencyIndex::default();
+        index.add_catalog_files(test_dir.path())?;
+        index.add_catalog_export(&test_dir.path().join("export.json"))?;
+
+        let index_path = test_dir.path().join("index.json");
+        index.save(&index_path)?;
+        let index = DependencyIndex::load(&index_path)?;
+
+        assert_eq!(
+            index.get("ledger-client"),
+            Some(&"component:default/ledger".to_string())
+        );
+        assert_eq!(
+            index.get("@bxbdigital/checkout"),
+            Some(&"component:shop/checkout".to_string())
+        );
+        assert_eq!(index.entries.len(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut index = DependencyIndex::default();
+        index.insert(
+            "bitbucket.org/bxbdigital/ledger-go",
+            "component:default/ledger",
+        );
+
+        assert_eq!(
+            index.resolve(&Component::at(
+                "bitbucket.org/bxbdigital/ledger-go/v3",
+                None
+            )),
+            "component:default/ledger"
+        );
+        assert_eq!(
+            index.resolve(&Component::at("@bxbdigital/cart-utils", None)),
+            "component:default/cart-utils"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+/// The Backstage `spec.type` inferred for a project and the rule that decided it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentTypeInference {
+    /// The component type, e.g. `service`, `library`, `website` or `cli`
+    pub r#type: String,
+
+    /// The name of the rule that fired, which can be used to override the type in the config
+    pub rule: String,
+}
+
+impl ComponentTypeInference {
+    pub fn new(r#type: &str, rule: &str) -> ComponentTypeInference {
+        ComponentTypeInference {
+            r#type: r#type.to_string(),
+            rule: rule.to_string(),
+        }
+    }
+}
+
+/// Checks if any of the dependency names is one of `candidates`.
+pub fn depends_on_any<'a>(
+    dependencies: impl IntoIterator<Item = &'a String>,
+    candidates: &[&str],
+) -> bool {
+    dependencies
+        .into_iter()
+        .any(|dependency| candidates.contains(&dependency.as_str()))
+}
+
+/// Finds the source files with `extension` in the project, skipping vendored and hidden
+/// directories.
+pub fn find_source_files(project_root: &Path, extension: &str) -> Vec<std::path::PathBuf> {
+    let mut source_files = vec![];
+    let mut directories = vec![project_root.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                if !file_name.starts_with('.')
+                    && !matches!(file_name.as_str(), "vendor" | "node_modules" | "target")
+                {
+                    directories.push(path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == extension) {
+                source_files.push(path);
+            }
+        }
+    }
+
+    source_files.sort();
+    source_files
+}
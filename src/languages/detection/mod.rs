@@ -0,0 +1,171 @@
+use regex::Regex;
+use std::path::Path;
+
+/// A file that marks a directory as the root of a project in a language.
+#[derive(Debug, Clone)]
+pub struct Marker {
+    /// A file name or a glob matched against the file names in the directory, e.g. `*.gemspec`
+    pub file: String,
+
+    /// A regex the contents of the file must match, e.g. `[package]` in a `Cargo.toml`
+    pub content: Option<String>,
+
+    /// How sure we are of the language when the marker matches, from 0 to 100
+    pub confidence: u8,
+}
+
+impl Marker {
+    /// A marker matched by the file name alone.
+    pub fn file(file: &str, confidence: u8) -> Marker {
+        Marker {
+            file: file.to_string(),
+            content: None,
+            confidence,
+        }
+    }
+
+    /// A marker that also requires the file's contents to match a regex.
+    pub fn content(file: &str, content: &str, confidence: u8) -> Marker {
+        Marker {
+            file: file.to_string(),
+            content: Some(content.to_string()),
+            confidence,
+        }
+    }
+}
+
+/// Describes how to recognise the root of a project in a language.
+#[derive(Debug, Clone, Default)]
+pub struct Detection {
+    /// The files that mark a project root
+    pub markers: Vec<Marker>,
+
+    /// Paths relative to the directory, or globs matched against its file names, that rule the
+    /// language out when they exist, e.g. `../Chart.yaml` for Helm chart templates
+    pub exclusions: Vec<String>,
+}
+
+impl Detection {
+    /// Returns the confidence of the strongest marker matching one of the files in `dir`, or
+    /// `None` if nothing matches or an exclusion applies.
+    pub fn detect(&self, dir: &Path, file_names: &[String]) -> Option<u8> {
+        let excluded = self.exclusions.iter().any(|exclusion| {
+            if is_glob(exclusion) {
+                file_names
+                    .iter()
+                    .any(|file_name| glob_match(exclusion, file_name))
+            } else {
+                dir.join(exclusion).exists()
+            }
+        });
+        if excluded {
+            return None;
+        }
+
+        self.markers
+            .iter()
+            .filter(|marker| {
+                file_names
+                    .iter()
+                    .filter(|file_name| glob_match(&marker.file, file_name))
+                    .any(|file_name| match &marker.content {
+                        Some(content) => content_matches(&dir.join(file_name), content),
+                        None => true,
+                    })
+            })
+            .map(|marker| marker.confidence)
+            .max()
+    }
+}
+
+fn content_matches(path: &Path, content: &str) -> bool {
+    let Ok(content_regex) = Regex::new(content) else {
+        return false;
+    };
+
+    std::fs::read_to_string(path).is_ok_and(|text| content_regex.is_match(&text))
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains('*') || pattern.contains('?')
+}
+
+/// Matches a file name against a glob supporting `*` and `?`.
+pub fn glob_match(pattern: &str, file_name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let file_name: Vec<char> = file_name.chars().collect();
+
+    let (mut p, mut f) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while f < file_name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == file_name[f]) {
+            p += 1;
+            f += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, f));
+            p += 1;
+        } else if let Some((star_p, star_f)) = backtrack {
+            p = star_p + 1;
+            f = star_f + 1;
+            backtrack = Some((star_p, star_f + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("Cargo.toml", "Cargo.toml"));
+        assert!(glob_match("*.gemspec", "billing.gemspec"));
+        assert!(glob_match("kustomization.y?ml", "kustomization.yaml"));
+        assert!(!glob_match("*.gemspec", "Gemfile"));
+        assert!(!glob_match("go.mod", "go.mod.bak"));
+    }
+
+    #[test]
+    fn test_detect() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("Cargo.toml"), "[workspace]\n")?;
+        std::fs::write(test_dir.path().join("deploy.yaml"), "kind: Deployment\n")?;
+        let file_names = vec!["Cargo.toml".to_string(), "deploy.yaml".to_string()];
+
+        let crate_detection = Detection {
+            markers: vec![Marker::content("Cargo.toml", r#"(?m)^\[package\]"#, 100)],
+            exclusions: vec![],
+        };
+        assert_eq!(crate_detection.detect(test_dir.path(), &file_names), None);
+
+        let manifest_detection = Detection {
+            markers: vec![
+                Marker::file("kustomization.yaml", 90),
+                Marker::content("*.yaml", r#"(?m)^kind:\s*Deployment"#, 50),
+            ],
+            exclusions: vec![],
+        };
+        assert_eq!(
+            manifest_detection.detect(test_dir.path(), &file_names),
+            Some(50)
+        );
+
+        let excluded_detection = Detection {
+            exclusions: vec!["Cargo.*".to_string()],
+            ..manifest_detection
+        };
+        assert_eq!(
+            excluded_detection.detect(test_dir.path(), &file_names),
+            None
+        );
+
+        Ok(())
+    }
+}
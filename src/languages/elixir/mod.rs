@@ -0,0 +1,367 @@
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, GitReference, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::ComponentTypeInference;
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::ProjectMetadata;
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use regex::Regex;
+use std::path::Path;
+
+const PROJECT_FILE: &str = "mix.exs";
+
+#[derive(Debug, Clone, Default)]
+pub struct Elixir {}
+
+impl Detect for Elixir {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![Marker::file(PROJECT_FILE, 100)],
+            exclusions: vec![],
+        }
+    }
+}
+
+impl Dependencies for Elixir {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let dependency_regex = Regex::new(r#"\{\s*:(\w+)\s*,([^}]*)\}"#).map_err(|source| {
+            GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+        })?;
+        let apps_path_regex = Regex::new(r#"apps_path:\s*"([^"]+)""#).map_err(|source| {
+            GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+        })?;
+        let dependency_source_regex =
+            Regex::new(r#"\b(git|github|organization|path):\s*"([^"]+)""#).map_err(|source| {
+                GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+            })?;
+        let reference_regex =
+            Regex::new(r#"\b(ref|tag|branch):\s*"([^"]+)""#).map_err(|source| {
+                GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+            })?;
+        // the version requirement is the first element after the dependency's name
+        let version_regex = Regex::new(r#"^\s*"([^"]+)""#).map_err(|source| {
+            GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+        })?;
+
+        let mix_exs_path = project_root.join(PROJECT_FILE);
+        let mix_exs_text = std::fs::read_to_string(&mix_exs_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+
+        let mut components: Vec<Component> = vec![];
+
+        // an umbrella project depends on each of the apps inside it
+        if let Some(apps_path) = apps_path_regex
+            .captures(&mix_exs_text)
+            .and_then(|captures| captures.get(1))
+        {
+            let mut apps: Vec<String> = std::fs::read_dir(project_root.join(apps_path.as_str()))
+                .map_err(|source| {
+                    GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+                })?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|app_path| app_path.join(PROJECT_FILE).exists())
+                .filter_map(|app_path| {
+                    app_path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .collect();
+            apps.sort();
+
+            let location =
+                SourceLocation::from_offset(&mix_exs_path, &mix_exs_text, apps_path.start());
+            components.extend(apps.into_iter().map(|name| {
+                Component::at(&name, Some(location.clone())).with_source(DependencySource::Path {
+                    path: format!("{}/{name}", apps_path.as_str()),
+                })
+            }));
+        }
+
+        for dependency in dependency_regex.captures_iter(&mix_exs_text) {
+            let Some(dependency_match) = dependency.get(0) else {
+                continue;
+            };
+            let name = dependency.get(1).map_or("", |m| m.as_str());
+            let options = dependency.get(2).map_or("", |m| m.as_str());
+
+            let reference = reference_regex.captures(options).map(|reference| {
+                let name = reference.get(2).map_or("", |m| m.as_str()).to_string();
+                match reference.get(1).map_or("", |m| m.as_str()) {
+                    "ref" => GitReference::Rev(name),
+                    "tag" => GitReference::Tag(name),
+                    _ => GitReference::Branch(name),
+                }
+            });
+
+            // umbrella apps sit next to each other in the umbrella's apps directory
+            let dependency_source =
+                if options.contains("in_umbrella: true") {
+                    Some(DependencySource::Path {
+                        path: format!("../{name}"),
+                    })
+                } else {
+                    dependency_source_regex
+                        .captures_iter(options)
+                        .find_map(|dependency_source| {
+                            let kind = dependency_source.get(1).map_or("", |m| m.as_str());
+                            let location = dependency_source.get(2).map_or("", |m| m.as_str());
+                            match kind {
+                                "path" => Some(DependencySource::Path {
+                                    path: location.to_string(),
+                                }),
+                                "git" => internal.is_internal_repo(location).then(|| {
+                                    DependencySource::Git {
+                                        url: location.to_string(),
+                                        reference: reference.clone(),
+                                    }
+                                }),
+                                // a shorthand for `org/repo` on GitHub
+                                "github" => location
+                                    .split_once('/')
+                                    .is_some_and(|(org, _)| internal.is_internal_org(org))
+                                    .then(|| DependencySource::Git {
+                                        url: format!("https://github.com/{location}.git"),
+                                        reference: reference.clone(),
+                                    }),
+                                // a private Hex organisation
+                                _ => internal.is_internal_org(location).then(|| {
+                                    DependencySource::Registry {
+                                        name: Some(location.to_string()),
+                                    }
+                                }),
+                            }
+                        })
+                };
+
+            if let Some(dependency_source) = dependency_source
+                && !components.iter().any(|component| component.name == name)
+            {
+                // git dependencies without a version requirement are pinned by their ref or tag
+                let version = version_regex
+                    .captures(options)
+                    .and_then(|version| version.get(1))
+                    .map(|version| version.as_str())
+                    .or(reference.as_ref().and_then(|reference| reference.version()));
+
+                let component = Component::at(
+                    name,
+                    Some(SourceLocation::from_offset(
+                        &mix_exs_path,
+                        &mix_exs_text,
+                        dependency_match.start(),
+                    )),
+                );
+                let component = match version {
+                    Some(version) => component.with_version(version),
+                    None => component,
+                };
+                components.push(component.with_source(dependency_source));
+            }
+        }
+
+        Ok(components)
+    }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("hex")
+    }
+}
+
+impl Annotations for Elixir {}
+
+impl Metadata for Elixir {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let field_regex =
+            Regex::new(r#"\b(description|version|source_url|homepage_url|docs_url):\s*"([^"]+)""#)
+                .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+        let list_regex = Regex::new(r#"\b(licenses|maintainers):\s*\[([^\]]*)\]"#)
+            .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+        let quoted_regex = Regex::new(r#""([^"]+)""#)
+            .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+
+        let mix_exs_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+
+        let mut metadata = ProjectMetadata::default();
+        for field in field_regex.captures_iter(&mix_exs_text) {
+            let value = field.get(2).map(|m| m.as_str().to_string());
+            match field.get(1).map_or("", |m| m.as_str()) {
+                "description" => metadata.description = value,
+                "version" => metadata.version = value,
+                "source_url" => metadata.repository = value,
+                "homepage_url" => metadata.homepage = value,
+                _ => metadata.documentation = value,
+            }
+        }
+
+        for list in list_regex.captures_iter(&mix_exs_text) {
+            let values: Vec<String> = list
+                .get(2)
+                .map(|values| {
+                    quoted_regex
+                        .captures_iter(values.as_str())
+                        .filter_map(|value| value.get(1))
+                        .map(|value| value.as_str().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            match list.get(1).map_or("", |m| m.as_str()) {
+                "licenses" => metadata.license = values.into_iter().next(),
+                _ => metadata.authors = values,
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let app_regex = Regex::new(r#"\bapp:\s*:(\w+)"#)
+            .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+
+        let mix_exs_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+
+        Ok(app_regex
+            .captures(&mix_exs_text)
+            .and_then(|app| app.get(1))
+            .map(|app| vec![app.as_str().to_string()])
+            .unwrap_or_default())
+    }
+}
+
+impl ComponentType for Elixir {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let mix_exs_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok()?;
+
+        if mix_exs_text.contains("{:phoenix,") || mix_exs_text.contains("{:plug_cowboy,") {
+            return Some(ComponentTypeInference::new(
+                "service",
+                "elixir-web-framework",
+            ));
+        }
+
+        if mix_exs_text.contains("escript:") {
+            return Some(ComponentTypeInference::new("cli", "elixir-escript"));
+        }
+
+        // an OTP application with a `mod:` callback runs on its own
+        if mix_exs_text.contains("mod: {") {
+            return Some(ComponentTypeInference::new("service", "elixir-application"));
+        }
+
+        Some(ComponentTypeInference::new("library", "elixir-library"))
+    }
+}
+
+impl Language for Elixir {}
+
+impl std::fmt::Display for Elixir {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Elixir")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("mix.exs"),
+            r#"defmodule Notifications.MixProject do
+  use Mix.Project
+
+  def project do
+    [app: :notifications, version: "0.1.0", deps: deps()]
+  end
+
+  defp deps do
+    [
+      {:phoenix, "~> 1.7"},
+      {:accounts, in_umbrella: true},
+      {:event_bus, git: "git@bitbucket.org:bxbdigital/event_bus.git", tag: "v1.2.0"},
+      {:telemetry_helpers, path: "../telemetry_helpers"},
+      {:billing, "~> 0.3", organization: "bxbdigital"},
+      {:jason, github: "michalmuskala/jason"}
+    ]
+  end
+end
+"#,
+        )?;
+
+        let dependencies = Elixir::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let summary: Vec<(&str, Option<&str>, String)> = dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.name.as_str(),
+                    dependency.version.as_deref(),
+                    dependency
+                        .source
+                        .as_ref()
+                        .map(|source| source.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("accounts", None, "path:../accounts".to_string()),
+                (
+                    "event_bus",
+                    Some("v1.2.0"),
+                    "git:git@bitbucket.org:bxbdigital/event_bus.git#tag=v1.2.0".to_string()
+                ),
+                (
+                    "telemetry_helpers",
+                    None,
+                    "path:../telemetry_helpers".to_string()
+                ),
+                ("billing", Some("~> 0.3"), "registry:bxbdigital".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_internal_dependencies_umbrella() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("mix.exs"),
+            r#"defmodule Platform.MixProject do
+  use Mix.Project
+
+  def project do
+    [apps_path: "apps", deps: []]
+  end
+end
+"#,
+        )?;
+        for app in ["web", "core"] {
+            std::fs::create_dir_all(test_dir.path().join("apps").join(app))?;
+            std::fs::write(test_dir.path().join("apps").join(app).join("mix.exs"), "")?;
+        }
+
+        let dependencies = Elixir::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+
+        assert_eq!(names, vec!["core", "web"]);
+
+        Ok(())
+    }
+}
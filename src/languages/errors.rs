@@ -11,6 +11,51 @@ pub enum GetInternalDependenciesError {
     #[error("Failed to compile internal repo regex: {source}")]
     FailedToCompileInternalRepoRegex { source: regex::Error },
 
+    #[error("Failed to compile dependency regex: {source}")]
+    FailedToCompileDependencyRegex { source: regex::Error },
+
     #[error("Failed to read the project Dependencies file: {source}")]
     FailedToReadProjectDependenciesFile { source: std::io::Error },
+
+    #[error("Failed to parse the project Dependencies file: {source}")]
+    FailedToParseProjectDependenciesFile { source: serde_yml::Error },
+
+    #[error("Failed to parse the project Dependencies JSON file: {source}")]
+    FailedToParseProjectDependenciesJsonFile { source: serde_json::Error },
+
+    #[error("Failed to parse the project Dependencies TOML file: {source}")]
+    FailedToParseProjectDependenciesTomlFile { source: toml::de::Error },
+
+    #[error("Failed to parse the project Dependencies XML file: {source}")]
+    FailedToParseProjectDependenciesXmlFile { source: quick_xml::DeError },
+}
+
+#[derive(Error, Debug)]
+pub enum GetAnnotationsError {
+    #[error("Failed to read the project file: {source}")]
+    FailedToReadProjectFile { source: std::io::Error },
+
+    #[error("Failed to parse the project file: {source}")]
+    FailedToParseProjectFile { source: serde_yml::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum GetMetadataError {
+    #[error("Failed to compile metadata regex: {source}")]
+    FailedToCompileMetadataRegex { source: regex::Error },
+
+    #[error("Failed to read the project manifest: {source}")]
+    FailedToReadProjectManifest { source: std::io::Error },
+
+    #[error("Failed to parse the project manifest: {source}")]
+    FailedToParseProjectManifest { source: serde_yml::Error },
+
+    #[error("Failed to parse the project JSON manifest: {source}")]
+    FailedToParseProjectJsonManifest { source: serde_json::Error },
+
+    #[error("Failed to parse the project TOML manifest: {source}")]
+    FailedToParseProjectTomlManifest { source: toml::de::Error },
+
+    #[error("Failed to parse the project XML manifest: {source}")]
+    FailedToParseProjectXmlManifest { source: quick_xml::DeError },
 }
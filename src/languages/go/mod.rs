@@ -1,20 +1,44 @@
-use super::errors::GetInternalDependenciesError;
-use crate::backstage::Component;
-use crate::languages::{Dependencies, Language};
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::{ComponentTypeInference, find_source_files};
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use crate::lockfile::{Lockfile, ReadLockfileError};
 use regex::Regex;
 use std::path::Path;
 
+const PROJECT_FILE: &str = "go.mod";
+
 #[derive(Debug, Clone, Default)]
 pub struct Go {}
 
-impl Dependencies for Go {
-    fn get_project_file_name(&self) -> String {
-        "go.mod".to_string()
+impl Detect for Go {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![Marker::file(PROJECT_FILE, 100)],
+            exclusions: vec![],
+        }
     }
+}
 
+impl Dependencies for Go {
     fn get_internal_dependencies(
         &self,
         project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        Ok(self
+            .get_all_dependencies(project_root, internal)?
+            .into_iter()
+            .filter(|component| internal.is_internal_repo(&component.name))
+            .collect())
+    }
+
+    fn get_all_dependencies(
+        &self,
+        project_root: &Path,
+        _internal: &InternalPatterns,
     ) -> Result<Vec<Component>, GetInternalDependenciesError> {
         let require_block_regex = Regex::new(r#"(require \((?:.*\n)*?\))"#).map_err(|source| {
             GetInternalDependenciesError::FailedToCompileRequireBlockRegex { source }
@@ -22,35 +46,178 @@ impl Dependencies for Go {
         let require_line_regex = Regex::new(r#"((\t(.+?) (.+)\n))"#).map_err(|source| {
             GetInternalDependenciesError::FailedToCompileRequireLineRegex { source }
         })?;
-        let internal_repo_regex =
-            Regex::new(r#".*bitbucket.org[\/:]bxbdigital\/.*"#).map_err(|source| {
-                GetInternalDependenciesError::FailedToCompileInternalRepoRegex { source }
-            })?;
+        // e.g. `require bitbucket.org/bxbdigital/ledger v1.2.3`
+        let single_require_regex = Regex::new(r#"(?m)^require[ \t]+([^\s(]+)[ \t]+(.+)$"#)
+            .map_err(
+                |source| GetInternalDependenciesError::FailedToCompileRequireLineRegex { source },
+            )?;
 
-        let mut components = vec![];
+        // the modules required and the offset of their name in the go.mod file
+        let mut requirements: Vec<(usize, &str, &str)> = vec![];
 
-        let project_file_text = std::fs::read_to_string(
-            project_root.join(self.get_project_file_name()),
-        )
-        .map_err(|source| {
+        let project_file_path = project_root.join(PROJECT_FILE);
+        let project_file_text = std::fs::read_to_string(&project_file_path).map_err(|source| {
             GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
         })?;
 
         for require_block in require_block_regex.captures_iter(&project_file_text) {
-            let require_block = require_block.get(1).map_or("", |m| m.as_str());
-            for require_line in require_line_regex.captures_iter(require_block) {
-                let module_name = require_line.get(3).map_or("", |m| m.as_str());
-
-                if internal_repo_regex.is_match(module_name) {
-                    components.push(Component {
-                        name: module_name.to_string(),
-                    });
-                }
+            let Some(require_block) = require_block.get(1) else {
+                continue;
+            };
+            for require_line in require_line_regex.captures_iter(require_block.as_str()) {
+                let (Some(module_name), Some(version)) = (require_line.get(3), require_line.get(4))
+                else {
+                    continue;
+                };
+                requirements.push((
+                    require_block.start() + module_name.start(),
+                    module_name.as_str(),
+                    version.as_str(),
+                ));
             }
         }
+        for require_line in single_require_regex.captures_iter(&project_file_text) {
+            let (Some(module_name), Some(version)) = (require_line.get(1), require_line.get(2))
+            else {
+                continue;
+            };
+            requirements.push((module_name.start(), module_name.as_str(), version.as_str()));
+        }
+        requirements.sort_by_key(|(offset, _, _)| *offset);
+
+        let mut components = vec![];
+        for (offset, module_name, version) in requirements {
+            let mut component = Component::at(
+                module_name,
+                Some(SourceLocation::from_offset(
+                    &project_file_path,
+                    &project_file_text,
+                    offset,
+                )),
+            );
+            // the version may be followed by a comment such as `// indirect`
+            if let Some(version) = version.split_whitespace().next() {
+                component = component.with_version(version);
+            }
+            if version.contains("// indirect") {
+                component = component.as_indirect();
+            }
+            components.push(replace(
+                component.with_source(DependencySource::Registry { name: None }),
+                &project_file_text,
+            ));
+        }
 
         Ok(components)
     }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("golang")
+    }
+
+    fn get_lockfile(&self, project_root: &Path) -> Result<Option<Lockfile>, ReadLockfileError> {
+        Lockfile::find(project_root, &["go.sum"])
+    }
+}
+
+/// Applies the `replace` directive for the component's module, if there is one. A module
+/// replaced by a local directory is fetched from that path, and one replaced by another module
+/// version is required at that version.
+fn replace(component: Component, go_mod_text: &str) -> Component {
+    for line in go_mod_text.lines() {
+        let Some((module, replacement)) = line.split_once("=>") else {
+            continue;
+        };
+        let module = module
+            .trim()
+            .trim_start_matches("replace")
+            .split_whitespace()
+            .next();
+        if module != Some(component.name.as_str()) {
+            continue;
+        }
+
+        let mut replacement = replacement.split_whitespace();
+        let Some(target) = replacement.next() else {
+            continue;
+        };
+        return if target.starts_with('.') || target.starts_with('/') {
+            Component {
+                version: None,
+                ..component
+            }
+            .with_source(DependencySource::Path {
+                path: target.to_string(),
+            })
+        } else {
+            match replacement.next() {
+                Some(version) => component.with_version(version),
+                None => component,
+            }
+        };
+    }
+
+    component
+}
+
+impl Annotations for Go {}
+
+impl Metadata for Go {
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let module_regex = Regex::new(r#"(?m)^module\s+(\S+)"#)
+            .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+
+        let go_mod_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+
+        Ok(module_regex
+            .captures(&go_mod_text)
+            .and_then(|module| module.get(1))
+            .map(|module| vec![module.as_str().to_string()])
+            .unwrap_or_default())
+    }
+}
+
+impl ComponentType for Go {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let main_package_regex = Regex::new(r#"(?m)^package main\s*$"#).ok()?;
+
+        let main_sources: Vec<String> = find_source_files(project_root, "go")
+            .into_iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .filter(|source| main_package_regex.is_match(source))
+            .collect();
+
+        if main_sources.is_empty() {
+            return Some(ComponentTypeInference::new("library", "go-library"));
+        }
+
+        let imports_any = |packages: &[&str]| {
+            main_sources.iter().any(|source| {
+                packages
+                    .iter()
+                    .any(|package| source.contains(&format!("\"{package}")))
+            })
+        };
+
+        if imports_any(&[
+            "net/http",
+            "github.com/gin-gonic/gin",
+            "github.com/labstack/echo",
+            "github.com/go-chi/chi",
+            "github.com/gofiber/fiber",
+            "github.com/gorilla/mux",
+            "google.golang.org/grpc",
+        ]) {
+            return Some(ComponentTypeInference::new("service", "go-http-service"));
+        }
+
+        if imports_any(&["github.com/spf13/cobra", "github.com/urfave/cli"]) {
+            return Some(ComponentTypeInference::new("cli", "go-cli"));
+        }
+
+        Some(ComponentTypeInference::new("service", "go-main-package"))
+    }
 }
 
 impl Language for Go {}
@@ -60,3 +227,56 @@ impl std::fmt::Display for Go {
         write!(f, "Go")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join(PROJECT_FILE),
+            "module bitbucket.org/bxbdigital/orders\n\n\
+             go 1.22\n\n\
+             require bitbucket.org/bxbdigital/ledger v1.2.3\n\n\
+             require (\n\
+             \tgithub.com/google/uuid v1.6.0\n\
+             \tbitbucket.org/bxbdigital/money v0.3.0 // indirect\n\
+             )\n\n\
+             require bitbucket.org/bxbdigital/audit v0.1.0 // indirect\n",
+        )?;
+
+        let dependencies = Go::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let summary: Vec<(&str, Option<&str>, bool, usize)> = dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.name.as_str(),
+                    dependency.version.as_deref(),
+                    dependency.indirect,
+                    dependency
+                        .location
+                        .as_ref()
+                        .map(|location| location.line)
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("bitbucket.org/bxbdigital/ledger", Some("v1.2.3"), false, 5),
+                ("bitbucket.org/bxbdigital/money", Some("v0.3.0"), true, 9),
+                ("bitbucket.org/bxbdigital/audit", Some("v0.1.0"), true, 12),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use super::errors::{GetAnnotationsError, GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::ComponentTypeInference;
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::ProjectMetadata;
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const PROJECT_FILE: &str = "Chart.yaml";
+
+#[derive(Debug, Clone, Default)]
+pub struct Helm {}
+
+/// The parts of a Helm `Chart.yaml` file that we care about.
+#[derive(Deserialize, Debug)]
+struct Chart {
+    name: String,
+    description: Option<String>,
+    version: Option<String>,
+    home: Option<String>,
+
+    #[serde(default)]
+    sources: Vec<String>,
+
+    #[serde(default)]
+    keywords: Vec<String>,
+
+    #[serde(default)]
+    maintainers: Vec<ChartMaintainer>,
+
+    #[serde(default)]
+    dependencies: Vec<ChartDependency>,
+}
+
+/// A maintainer listed in a `Chart.yaml` file.
+#[derive(Deserialize, Debug)]
+struct ChartMaintainer {
+    name: String,
+}
+
+/// A sub-chart listed in the `dependencies` block of a `Chart.yaml` file.
+#[derive(Deserialize, Debug)]
+struct ChartDependency {
+    name: String,
+    version: Option<String>,
+
+    #[serde(default)]
+    repository: String,
+}
+
+impl Detect for Helm {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![Marker::content(PROJECT_FILE, r#"(?m)^name:"#, 100)],
+            exclusions: vec![],
+        }
+    }
+}
+
+impl Dependencies for Helm {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        // local sub-charts (file://) and charts from our own repositories are internal
+        let chart_path = project_root.join(PROJECT_FILE);
+        let chart_text = std::fs::read_to_string(&chart_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let chart: Chart = serde_yml::from_str(&chart_text).map_err(|source| {
+            GetInternalDependenciesError::FailedToParseProjectDependenciesFile { source }
+        })?;
+
+        Ok(chart
+            .dependencies
+            .into_iter()
+            .filter(|dependency| {
+                dependency.repository.starts_with("file://")
+                    || internal.is_internal_source(&dependency.repository)
+            })
+            .map(|dependency| {
+                let location = SourceLocation::find(
+                    &chart_path,
+                    &chart_text,
+                    &format!("name: {}", dependency.name),
+                );
+                let source = match dependency.repository.strip_prefix("file://") {
+                    Some(path) => DependencySource::Path {
+                        path: path.to_string(),
+                    },
+                    None => DependencySource::Registry {
+                        name: Some(dependency.repository.clone()),
+                    },
+                };
+                let component = Component::at(&dependency.name, location).with_source(source);
+                match dependency.version {
+                    Some(version) => component.with_version(&version),
+                    None => component,
+                }
+            })
+            .collect())
+    }
+}
+
+impl Annotations for Helm {
+    fn get_annotations(
+        &self,
+        project_root: &Path,
+    ) -> Result<BTreeMap<String, String>, GetAnnotationsError> {
+        let chart_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetAnnotationsError::FailedToReadProjectFile { source })?;
+        let chart: Chart = serde_yml::from_str(&chart_text)
+            .map_err(|source| GetAnnotationsError::FailedToParseProjectFile { source })?;
+
+        // Helm charts conventionally label their resources with the chart name
+        Ok(BTreeMap::from([
+            ("backstage.io/kubernetes-id".to_string(), chart.name.clone()),
+            (
+                "backstage.io/kubernetes-label-selector".to_string(),
+                format!("app.kubernetes.io/name={}", chart.name),
+            ),
+        ]))
+    }
+}
+
+impl Metadata for Helm {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let chart_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let chart: Chart = serde_yml::from_str(&chart_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectManifest { source })?;
+
+        Ok(ProjectMetadata {
+            description: chart.description,
+            version: chart.version,
+            authors: chart
+                .maintainers
+                .into_iter()
+                .map(|maintainer| maintainer.name)
+                .collect(),
+            license: None,
+            homepage: chart.home,
+            repository: chart.sources.into_iter().next(),
+            documentation: None,
+            keywords: chart.keywords,
+        })
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let chart_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let chart: Chart = serde_yml::from_str(&chart_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectManifest { source })?;
+
+        Ok(vec![chart.name])
+    }
+}
+
+impl ComponentType for Helm {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let chart_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok()?;
+
+        // library charts only provide templates to other charts
+        if chart_text
+            .lines()
+            .any(|line| line.trim() == "type: library")
+        {
+            return Some(ComponentTypeInference::new("library", "helm-library-chart"));
+        }
+
+        Some(ComponentTypeInference::new("service", "helm-chart"))
+    }
+}
+
+impl Language for Helm {}
+
+impl std::fmt::Display for Helm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Helm")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    const CHART: &str = r#"apiVersion: v2
+name: payments
+version: 0.1.0
+dependencies:
+  - name: common
+    version: 1.2.0
+    repository: file://../common
+  - name: ledger
+    version: 0.4.0
+    repository: oci://registry.bxbdigital.io/charts
+  - name: postgresql
+    version: 12.1.0
+    repository: https://charts.bitnami.com/bitnami
+  - name: mirror
+    version: 1.0.0
+    repository: https://charts.example.com/bxbdigital-mirror
+"#;
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("Chart.yaml"), CHART)?;
+
+        let dependencies = Helm::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+
+        assert_eq!(names, vec!["common", "ledger"]);
+        assert_eq!(dependencies[0].version.as_deref(), Some("1.2.0"));
+        assert_eq!(
+            dependencies[0].source,
+            Some(DependencySource::Path {
+                path: "../common".to_string()
+            })
+        );
+        assert_eq!(dependencies[1].version.as_deref(), Some("0.4.0"));
+        assert_eq!(
+            dependencies[1].source,
+            Some(DependencySource::Registry {
+                name: Some("oci://registry.bxbdigital.io/charts".to_string())
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_annotations() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("Chart.yaml"), CHART)?;
+
+        let annotations = Helm::default().get_annotations(test_dir.path())?;
+
+        assert_eq!(
+            annotations.get("backstage.io/kubernetes-id"),
+            Some(&"payments".to_string())
+        );
+        assert_eq!(
+            annotations.get("backstage.io/kubernetes-label-selector"),
+            Some(&"app.kubernetes.io/name=payments".to_string())
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::ComponentTypeInference;
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::ProjectMetadata;
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use serde::Deserialize;
+use std::path::Path;
+
+const PROJECT_FILE: &str = "pom.xml";
+
+#[derive(Debug, Clone, Default)]
+pub struct Java {}
+
+/// The parts of a Maven `pom.xml` file that we care about.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct Pom {
+    group_id: Option<String>,
+    artifact_id: Option<String>,
+    description: Option<String>,
+    version: Option<String>,
+    packaging: Option<String>,
+    url: Option<String>,
+    licenses: Option<PomLicenses>,
+    developers: Option<PomDevelopers>,
+    scm: Option<PomScm>,
+    dependencies: Option<PomDependencies>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PomLicenses {
+    #[serde(default)]
+    license: Vec<PomLicense>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PomLicense {
+    name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PomDevelopers {
+    #[serde(default)]
+    developer: Vec<PomDeveloper>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PomDeveloper {
+    name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PomScm {
+    url: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PomDependencies {
+    #[serde(default)]
+    dependency: Vec<PomDependency>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PomDependency {
+    group_id: String,
+    artifact_id: String,
+    version: Option<String>,
+}
+
+impl Detect for Java {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![Marker::file(PROJECT_FILE, 100)],
+            exclusions: vec![],
+        }
+    }
+}
+
+impl Dependencies for Java {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let pom_path = project_root.join(PROJECT_FILE);
+        let pom_text = std::fs::read_to_string(&pom_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let pom: Pom = quick_xml::de::from_str(&pom_text).map_err(|source| {
+            GetInternalDependenciesError::FailedToParseProjectDependenciesXmlFile { source }
+        })?;
+
+        Ok(pom
+            .dependencies
+            .map(|dependencies| dependencies.dependency)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|dependency| internal.is_internal_group(&dependency.group_id))
+            .map(|dependency| {
+                let location = SourceLocation::find(
+                    &pom_path,
+                    &pom_text,
+                    &format!("<artifactId>{}</artifactId>", dependency.artifact_id),
+                );
+                with_pom_version(
+                    Component::at(&dependency.artifact_id, location),
+                    dependency.version,
+                )
+            })
+            .collect())
+    }
+
+    /// Dependencies are named by their Maven coordinates, e.g. `com.bxbdigital:ledger`.
+    fn get_all_dependencies(
+        &self,
+        project_root: &Path,
+        _internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let pom_path = project_root.join(PROJECT_FILE);
+        let pom_text = std::fs::read_to_string(&pom_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let pom: Pom = quick_xml::de::from_str(&pom_text).map_err(|source| {
+            GetInternalDependenciesError::FailedToParseProjectDependenciesXmlFile { source }
+        })?;
+
+        Ok(pom
+            .dependencies
+            .map(|dependencies| dependencies.dependency)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|dependency| {
+                let location = SourceLocation::find(
+                    &pom_path,
+                    &pom_text,
+                    &format!("<artifactId>{}</artifactId>", dependency.artifact_id),
+                );
+                with_pom_version(
+                    Component::at(
+                        &format!("{}:{}", dependency.group_id, dependency.artifact_id),
+                        location,
+                    ),
+                    dependency.version,
+                )
+            })
+            .collect())
+    }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("maven")
+    }
+}
+
+/// Records the version a `pom.xml` requires a dependency at, which Maven fetches from a
+/// repository.
+fn with_pom_version(component: Component, version: Option<String>) -> Component {
+    let component = component.with_source(DependencySource::Registry { name: None });
+    // versions from properties, e.g. `${ledger.version}`, aren't resolved
+    match version {
+        Some(version) if !version.starts_with("${") => component.with_version(&version),
+        _ => component,
+    }
+}
+
+impl Annotations for Java {}
+
+impl Metadata for Java {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let pom_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let pom: Pom = quick_xml::de::from_str(&pom_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectXmlManifest { source })?;
+
+        Ok(ProjectMetadata {
+            description: pom
+                .description
+                .map(|description| description.split_whitespace().collect::<Vec<_>>().join(" ")),
+            version: pom.version,
+            authors: pom
+                .developers
+                .map(|developers| developers.developer)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|developer| developer.name)
+                .collect(),
+            license: pom
+                .licenses
+                .and_then(|licenses| licenses.license.into_iter().next())
+                .and_then(|license| license.name),
+            homepage: pom.url,
+            repository: pom.scm.and_then(|scm| scm.url),
+            documentation: None,
+            keywords: vec![],
+        })
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let pom_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let pom: Pom = quick_xml::de::from_str(&pom_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectXmlManifest { source })?;
+
+        // dependencies are declared by artifact id, and Maven coordinates include the group id
+        let Some(artifact_id) = pom.artifact_id else {
+            return Ok(vec![]);
+        };
+        let mut names = vec![artifact_id.clone()];
+        if let Some(group_id) = pom.group_id {
+            names.push(format!("{group_id}:{artifact_id}"));
+        }
+
+        Ok(names)
+    }
+}
+
+impl ComponentType for Java {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let pom_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok()?;
+        let pom: Pom = quick_xml::de::from_str(&pom_text).ok()?;
+
+        let artifacts: Vec<String> = pom
+            .dependencies
+            .map(|dependencies| dependencies.dependency)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|dependency| dependency.artifact_id)
+            .collect();
+
+        if artifacts.iter().any(|artifact| {
+            artifact.starts_with("spring-boot-starter-web")
+                || artifact.starts_with("quarkus-rest")
+                || artifact.starts_with("quarkus-resteasy")
+                || artifact.starts_with("micronaut-http-server")
+        }) {
+            return Some(ComponentTypeInference::new("service", "java-web-framework"));
+        }
+
+        if pom.packaging.as_deref() == Some("war") {
+            return Some(ComponentTypeInference::new("service", "java-war"));
+        }
+
+        Some(ComponentTypeInference::new("library", "java-artifact"))
+    }
+}
+
+impl Language for Java {}
+
+impl std::fmt::Display for Java {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Java")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_metadata() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("pom.xml"),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <parent>
+    <groupId>com.bxbdigital</groupId>
+    <artifactId>service-parent</artifactId>
+    <version>3.0.0</version>
+  </parent>
+  <groupId>com.bxbdigital.inventory</groupId>
+  <artifactId>inventory-service</artifactId>
+  <version>1.4.0</version>
+  <description>
+    Tracks stock levels
+    across warehouses
+  </description>
+  <url>https://inventory.example.com</url>
+  <licenses>
+    <license><name>Apache-2.0</name></license>
+  </licenses>
+  <developers>
+    <developer><name>Jane Doe</name></developer>
+  </developers>
+  <dependencies>
+    <dependency>
+      <groupId>com.bxbdigital.shared</groupId>
+      <artifactId>audit-client</artifactId>
+      <version>2.1.0</version>
+    </dependency>
+    <dependency>
+      <groupId>org.springframework.boot</groupId>
+      <artifactId>spring-boot-starter-web</artifactId>
+    </dependency>
+  </dependencies>
+</project>
+"#,
+        )?;
+
+        let metadata = Java::default().get_metadata(test_dir.path())?;
+        assert_eq!(
+            metadata.description,
+            Some("Tracks stock levels across warehouses".to_string())
+        );
+        assert_eq!(metadata.version, Some("1.4.0".to_string()));
+        assert_eq!(metadata.license, Some("Apache-2.0".to_string()));
+        assert_eq!(metadata.authors, vec!["Jane Doe".to_string()]);
+
+        let dependencies = Java::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(names, vec!["audit-client"]);
+        assert_eq!(dependencies[0].version.as_deref(), Some("2.1.0"));
+        assert_eq!(
+            dependencies[0].source,
+            Some(DependencySource::Registry { name: None })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, GitReference, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::{ComponentTypeInference, depends_on_any};
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::{ProjectMetadata, author_name};
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use crate::lockfile::{Lockfile, ReadLockfileError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const PROJECT_FILE: &str = "package.json";
+
+#[derive(Debug, Clone, Default)]
+pub struct JavaScript {}
+
+/// The parts of a `package.json` file that we care about.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PackageJson {
+    description: Option<String>,
+    version: Option<String>,
+    homepage: Option<String>,
+
+    /// Either a name like `Jane Doe <jane@example.com>` or an object with a `name`
+    #[serde(default)]
+    author: serde_json::Value,
+
+    #[serde(default)]
+    contributors: Vec<serde_json::Value>,
+
+    /// Either an SPDX expression or a legacy object with a `type`
+    #[serde(default)]
+    license: serde_json::Value,
+
+    /// Either a URL or an object with a `url`
+    #[serde(default)]
+    repository: serde_json::Value,
+
+    #[serde(default)]
+    keywords: Vec<String>,
+
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, String>,
+
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, String>,
+
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+}
+
+/// Reads a person from a `package.json`, which is either a string or an object with a `name`.
+fn person_name(person: &serde_json::Value) -> Option<String> {
+    match person {
+        serde_json::Value::String(person) => Some(author_name(person)),
+        serde_json::Value::Object(person) => person
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(|name| name.to_string()),
+        _ => None,
+    }
+}
+
+/// Reads a string field that may also be an object with the string under `key`.
+fn string_or_field(value: &serde_json::Value, key: &str) -> Option<String> {
+    match value {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Object(object) => object
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string()),
+        _ => None,
+    }
+}
+
+impl Detect for JavaScript {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![Marker::file(PROJECT_FILE, 100)],
+            exclusions: vec![],
+        }
+    }
+}
+
+impl Dependencies for JavaScript {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let package_json_path = project_root.join(PROJECT_FILE);
+        let package_json_text = std::fs::read_to_string(&package_json_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let package_json: PackageJson =
+            serde_json::from_str(&package_json_text).map_err(|source| {
+                GetInternalDependenciesError::FailedToParseProjectDependenciesJsonFile { source }
+            })?;
+
+        let mut components: Vec<Component> = vec![];
+        for (package, version) in package_json
+            .dependencies
+            .iter()
+            .chain(&package_json.dev_dependencies)
+            .chain(&package_json.peer_dependencies)
+            .chain(&package_json.optional_dependencies)
+        {
+            if !internal.is_internal_scope(package) && !internal.is_internal_repo(version) {
+                continue;
+            }
+
+            // the scope is kept, as packages are indexed under their full name
+            if components
+                .iter()
+                .any(|component| component.name == *package)
+            {
+                continue;
+            }
+
+            components.push(dependency_component(
+                package,
+                version,
+                &package_json_path,
+                &package_json_text,
+            ));
+        }
+
+        Ok(components)
+    }
+
+    fn get_all_dependencies(
+        &self,
+        project_root: &Path,
+        _internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let package_json_path = project_root.join(PROJECT_FILE);
+        let package_json_text = std::fs::read_to_string(&package_json_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let package_json: PackageJson =
+            serde_json::from_str(&package_json_text).map_err(|source| {
+                GetInternalDependenciesError::FailedToParseProjectDependenciesJsonFile { source }
+            })?;
+
+        let mut components: Vec<Component> = vec![];
+        for (package, version) in package_json
+            .dependencies
+            .iter()
+            .chain(&package_json.dev_dependencies)
+            .chain(&package_json.peer_dependencies)
+            .chain(&package_json.optional_dependencies)
+        {
+            if !components
+                .iter()
+                .any(|component| &component.name == package)
+            {
+                components.push(dependency_component(
+                    package,
+                    version,
+                    &package_json_path,
+                    &package_json_text,
+                ));
+            }
+        }
+
+        Ok(components)
+    }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("npm")
+    }
+
+    fn get_lockfile(&self, project_root: &Path) -> Result<Option<Lockfile>, ReadLockfileError> {
+        Lockfile::find(
+            project_root,
+            &["package-lock.json", "yarn.lock", "pnpm-lock.yaml"],
+        )
+    }
+}
+
+impl Annotations for JavaScript {}
+
+impl Metadata for JavaScript {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let package_json_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let package_json: PackageJson = serde_json::from_str(&package_json_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectJsonManifest { source })?;
+
+        let authors = std::iter::once(&package_json.author)
+            .chain(&package_json.contributors)
+            .filter_map(person_name)
+            .collect();
+
+        Ok(ProjectMetadata {
+            description: package_json.description,
+            version: package_json.version,
+            authors,
+            license: string_or_field(&package_json.license, "type"),
+            homepage: package_json.homepage,
+            repository: string_or_field(&package_json.repository, "url"),
+            documentation: None,
+            keywords: package_json.keywords,
+        })
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let package_json_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let package_json: serde_json::Value = serde_json::from_str(&package_json_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectJsonManifest { source })?;
+
+        Ok(package_json
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(|name| vec![name.to_string()])
+            .unwrap_or_default())
+    }
+}
+
+impl ComponentType for JavaScript {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let package_json_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok()?;
+        let package_json: serde_json::Value = serde_json::from_str(&package_json_text).ok()?;
+
+        let dependencies: Vec<String> = ["dependencies", "devDependencies", "peerDependencies"]
+            .iter()
+            .filter_map(|key| package_json.get(key).and_then(|value| value.as_object()))
+            .flat_map(|dependencies| dependencies.keys().cloned())
+            .collect();
+
+        if depends_on_any(
+            &dependencies,
+            &[
+                "react",
+                "react-dom",
+                "vue",
+                "@angular/core",
+                "svelte",
+                "next",
+                "nuxt",
+                "gatsby",
+                "@remix-run/react",
+            ],
+        ) {
+            return Some(ComponentTypeInference::new(
+                "website",
+                "javascript-frontend-framework",
+            ));
+        }
+
+        if depends_on_any(
+            &dependencies,
+            &[
+                "express",
+                "fastify",
+                "koa",
+                "@nestjs/core",
+                "@hapi/hapi",
+                "restify",
+            ],
+        ) {
+            return Some(ComponentTypeInference::new(
+                "service",
+                "javascript-http-server",
+            ));
+        }
+
+        if package_json.get("bin").is_some() {
+            return Some(ComponentTypeInference::new("cli", "javascript-bin"));
+        }
+
+        Some(ComponentTypeInference::new("library", "javascript-package"))
+    }
+}
+
+impl Language for JavaScript {}
+
+impl std::fmt::Display for JavaScript {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "JavaScript")
+    }
+}
+
+/// The component for the dependency on `package` at `version_spec`.
+fn dependency_component(
+    package: &str,
+    version_spec: &str,
+    package_json_path: &Path,
+    package_json_text: &str,
+) -> Component {
+    let mut component = Component::at(
+        package,
+        SourceLocation::find(
+            package_json_path,
+            package_json_text,
+            &format!("\"{package}\""),
+        ),
+    );
+    let (version, source) = parse_version_spec(version_spec);
+    if let Some(version) = version {
+        component = component.with_version(&version);
+    }
+    component.with_source(source)
+}
+
+/// Splits a dependency's version spec, e.g. `^1.2.0`, `file:../ui` or
+/// `git+ssh://git@bitbucket.org/bxbdigital/ui.git#v1.2.0`, into the version required and where
+/// the package is fetched from. Git dependencies are pinned by their commit-ish.
+fn parse_version_spec(spec: &str) -> (Option<String>, DependencySource) {
+    let spec = spec.trim();
+
+    for prefix in ["file:", "link:"] {
+        if let Some(path) = spec.strip_prefix(prefix) {
+            let source = DependencySource::Path {
+                path: path.to_string(),
+            };
+            return (None, source);
+        }
+    }
+
+    let is_git = ["git+", "git:", "git@", "github:", "gitlab:", "bitbucket:"]
+        .iter()
+        .any(|prefix| spec.starts_with(prefix))
+        || spec
+            .split('#')
+            .next()
+            .is_some_and(|url| url.ends_with(".git"));
+    if is_git {
+        let (url, committish) = match spec.split_once('#') {
+            Some((url, committish)) => (url, Some(committish)),
+            None => (spec, None),
+        };
+        let reference = committish.map(|committish| match committish.strip_prefix("semver:") {
+            Some(range) => GitReference::Tag(range.to_string()),
+            None if committish.len() == 40 && committish.chars().all(|c| c.is_ascii_hexdigit()) => {
+                GitReference::Rev(committish.to_string())
+            }
+            None => GitReference::Tag(committish.to_string()),
+        });
+        let version = reference
+            .as_ref()
+            .map(|reference| reference.name().to_string());
+        let source = DependencySource::Git {
+            url: url.trim_start_matches("git+").to_string(),
+            reference,
+        };
+        return (version, source);
+    }
+
+    // aliases such as `npm:@bxbdigital/ui@^2.0.0` name the package before its version
+    let version = match spec.strip_prefix("npm:") {
+        Some(alias) => alias
+            .rsplit_once('@')
+            .map(|(_, version)| version)
+            .unwrap_or(""),
+        None => spec,
+    };
+    let version = (!version.is_empty()).then(|| version.to_string());
+    (version, DependencySource::Registry { name: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_metadata() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("package.json"),
+            r#"{
+  "name": "@bxbdigital/checkout-web",
+  "version": "4.2.0",
+  "description": "The checkout single page app",
+  "author": "Jane Doe <jane@example.com>",
+  "license": "MIT",
+  "homepage": "https://checkout.example.com",
+  "repository": { "type": "git", "url": "https://bitbucket.org/bxbdigital/checkout-web" },
+  "keywords": ["checkout", "react"],
+  "dependencies": {
+    "@bxbdigital/design-system": "^12.0.0",
+    "cart-utils": "git+ssh://git@bitbucket.org/bxbdigital/cart-utils.git#v1.0.0",
+    "react": "^18.2.0"
+  }
+}"#,
+        )?;
+
+        let metadata = JavaScript::default().get_metadata(test_dir.path())?;
+        assert_eq!(
+            metadata,
+            ProjectMetadata {
+                description: Some("The checkout single page app".to_string()),
+                version: Some("4.2.0".to_string()),
+                authors: vec!["Jane Doe".to_string()],
+                license: Some("MIT".to_string()),
+                homepage: Some("https://checkout.example.com".to_string()),
+                repository: Some("https://bitbucket.org/bxbdigital/checkout-web".to_string()),
+                documentation: None,
+                keywords: vec!["checkout".to_string(), "react".to_string()],
+            }
+        );
+
+        let dependencies = JavaScript::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(names, vec!["@bxbdigital/design-system", "cart-utils"]);
+        let versions: Vec<Option<&str>> = dependencies
+            .iter()
+            .map(|dep| dep.version.as_deref())
+            .collect();
+        assert_eq!(versions, vec![Some("^12.0.0"), Some("v1.0.0")]);
+        assert_eq!(
+            dependencies[1].source,
+            Some(DependencySource::Git {
+                url: "ssh://git@bitbucket.org/bxbdigital/cart-utils.git".to_string(),
+                reference: Some(GitReference::Tag("v1.0.0".to_string())),
+            })
+        );
+
+        Ok(())
+    }
+}
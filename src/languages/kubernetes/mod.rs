@@ -0,0 +1,385 @@
+use super::errors::{GetAnnotationsError, GetInternalDependenciesError};
+use crate::backstage::{Component, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::ComponentTypeInference;
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The kinds of Kubernetes resource that make a directory of manifests a deployable unit.
+const WORKLOAD_KINDS: [&str; 5] = ["Deployment", "StatefulSet", "DaemonSet", "CronJob", "Job"];
+
+/// The file names kustomize looks for, in the order it prefers them.
+const KUSTOMIZATION_FILES: [&str; 3] = ["kustomization.yaml", "kustomization.yml", "Kustomization"];
+
+#[derive(Debug, Clone, Default)]
+pub struct Kubernetes {}
+
+/// The parts of a `kustomization.yaml` file that we care about.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct Kustomization {
+    #[serde(default)]
+    resources: Vec<String>,
+
+    #[serde(default)]
+    bases: Vec<String>,
+
+    #[serde(default)]
+    common_labels: BTreeMap<String, String>,
+
+    #[serde(default)]
+    labels: Vec<KustomizationLabels>,
+}
+
+#[derive(Deserialize, Debug)]
+struct KustomizationLabels {
+    #[serde(default)]
+    pairs: BTreeMap<String, String>,
+}
+
+/// The parts of a raw Kubernetes manifest that we care about.
+#[derive(Deserialize, Debug)]
+struct Manifest {
+    kind: Option<String>,
+    metadata: Option<ManifestMetadata>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ManifestMetadata {
+    name: Option<String>,
+
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+impl Kubernetes {
+    /// Parses every YAML document in the file, skipping documents that are not manifests.
+    fn read_manifests(path: &Path) -> Vec<Manifest> {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return vec![];
+        };
+
+        serde_yml::Deserializer::from_str(&text)
+            .filter_map(|document| Manifest::deserialize(document).ok())
+            .collect()
+    }
+
+    /// Returns the workload manifests in the YAML files directly inside the project root.
+    fn read_workloads(project_root: &Path) -> Vec<Manifest> {
+        let Ok(entries) = std::fs::read_dir(project_root) else {
+            return vec![];
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_yaml_file(path))
+            .collect();
+        paths.sort();
+
+        paths
+            .iter()
+            .flat_map(|path| Kubernetes::read_manifests(path))
+            .filter(Manifest::is_workload)
+            .collect()
+    }
+
+    /// Returns the path of the project's kustomization file, under whichever of its names.
+    fn find_kustomization(project_root: &Path) -> Option<PathBuf> {
+        KUSTOMIZATION_FILES
+            .iter()
+            .map(|file_name| project_root.join(file_name))
+            .find(|path| path.is_file())
+    }
+
+    fn read_kustomization(
+        &self,
+        project_root: &Path,
+    ) -> Result<Option<Kustomization>, GetAnnotationsError> {
+        let Some(kustomization_path) = Kubernetes::find_kustomization(project_root) else {
+            return Ok(None);
+        };
+
+        let kustomization_text = std::fs::read_to_string(kustomization_path)
+            .map_err(|source| GetAnnotationsError::FailedToReadProjectFile { source })?;
+
+        serde_yml::from_str(&kustomization_text)
+            .map_err(|source| GetAnnotationsError::FailedToParseProjectFile { source })
+    }
+}
+
+impl Manifest {
+    fn is_workload(&self) -> bool {
+        self.kind
+            .as_deref()
+            .is_some_and(|kind| WORKLOAD_KINDS.contains(&kind))
+    }
+}
+
+fn is_yaml_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension == "yaml" || extension == "yml")
+}
+
+impl Detect for Kubernetes {
+    fn detection(&self) -> Detection {
+        let workload_regex = format!(r#"(?m)^kind:\s*({})\s*$"#, WORKLOAD_KINDS.join("|"));
+
+        // charts and their templates are covered by Helm
+        Detection {
+            markers: vec![
+                Marker::file(KUSTOMIZATION_FILES[0], 90),
+                Marker::file(KUSTOMIZATION_FILES[1], 90),
+                Marker::file(KUSTOMIZATION_FILES[2], 90),
+                Marker::content("*.yaml", &workload_regex, 50),
+                Marker::content("*.yml", &workload_regex, 50),
+            ],
+            exclusions: vec!["Chart.yaml".to_string(), "../Chart.yaml".to_string()],
+        }
+    }
+}
+
+impl Dependencies for Kubernetes {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let Some(kustomization_path) = Kubernetes::find_kustomization(project_root) else {
+            return Ok(vec![]);
+        };
+
+        let kustomization_text =
+            std::fs::read_to_string(&kustomization_path).map_err(|source| {
+                GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+            })?;
+        let kustomization: Kustomization =
+            serde_yml::from_str(&kustomization_text).map_err(|source| {
+                GetInternalDependenciesError::FailedToParseProjectDependenciesFile { source }
+            })?;
+
+        // remote bases in our own repositories are internal dependencies
+        Ok(kustomization
+            .resources
+            .iter()
+            .chain(kustomization.bases.iter())
+            .filter_map(|resource| {
+                let repo_name = internal.repo_name(resource)?;
+                Some(Component::at(
+                    repo_name,
+                    SourceLocation::find(&kustomization_path, &kustomization_text, resource),
+                ))
+            })
+            .collect())
+    }
+}
+
+impl Annotations for Kubernetes {
+    fn get_annotations(
+        &self,
+        project_root: &Path,
+    ) -> Result<BTreeMap<String, String>, GetAnnotationsError> {
+        let workloads = Kubernetes::read_workloads(project_root);
+        let workload_metadata = workloads.iter().find_map(|w| w.metadata.as_ref());
+
+        // prefer the labels kustomize stamps onto every resource, then the workload's own labels
+        let mut labels = BTreeMap::new();
+        if let Some(kustomization) = self.read_kustomization(project_root)? {
+            labels.extend(kustomization.common_labels);
+            for label_set in kustomization.labels {
+                labels.extend(label_set.pairs);
+            }
+        }
+        if labels.is_empty()
+            && let Some(metadata) = workload_metadata
+        {
+            labels.extend(metadata.labels.clone());
+        }
+
+        let kubernetes_id = labels
+            .get("app.kubernetes.io/name")
+            .or_else(|| labels.get("app"))
+            .cloned()
+            .or_else(|| workload_metadata.and_then(|metadata| metadata.name.clone()))
+            .or_else(|| {
+                project_root
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            });
+
+        let mut annotations = BTreeMap::new();
+        if let Some(kubernetes_id) = kubernetes_id {
+            annotations.insert("backstage.io/kubernetes-id".to_string(), kubernetes_id);
+        }
+        if !labels.is_empty() {
+            let label_selector = labels
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            annotations.insert(
+                "backstage.io/kubernetes-label-selector".to_string(),
+                label_selector,
+            );
+        }
+
+        Ok(annotations)
+    }
+}
+
+impl Metadata for Kubernetes {}
+
+impl ComponentType for Kubernetes {
+    fn infer_component_type(&self, _project_root: &Path) -> Option<ComponentTypeInference> {
+        Some(ComponentTypeInference::new(
+            "service",
+            "kubernetes-manifests",
+        ))
+    }
+}
+
+impl Language for Kubernetes {}
+
+impl std::fmt::Display for Kubernetes {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Kubernetes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    const DEPLOYMENT: &str = r#"apiVersion: v1
+kind: Service
+metadata:
+  name: orders
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: orders
+  labels:
+    app.kubernetes.io/name: orders
+    team: checkout
+"#;
+
+    #[test]
+    fn test_detect() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("deployment.yaml"), DEPLOYMENT)?;
+        std::fs::write(test_dir.path().join("config.yaml"), "log_level: debug\n")?;
+        let file_names = vec!["config.yaml".to_string(), "deployment.yaml".to_string()];
+
+        assert_eq!(
+            Kubernetes::default().detect(test_dir.path(), &file_names),
+            Some(50)
+        );
+        assert_eq!(
+            Kubernetes::default().detect(test_dir.path(), &file_names[..1]),
+            None
+        );
+
+        // templates inside a Helm chart belong to the chart
+        let templates_dir = test_dir.path().join("templates");
+        std::fs::create_dir(&templates_dir)?;
+        std::fs::write(templates_dir.join("deployment.yaml"), DEPLOYMENT)?;
+        std::fs::write(test_dir.path().join("Chart.yaml"), "name: orders\n")?;
+        assert_eq!(
+            Kubernetes::default().detect(&templates_dir, &file_names[1..]),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_annotations_from_kustomization() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("deployment.yaml"), DEPLOYMENT)?;
+        std::fs::write(
+            test_dir.path().join("kustomization.yaml"),
+            r#"resources:
+  - deployment.yaml
+  - https://bitbucket.org/bxbdigital/base-manifests//deploy?ref=v1.0.0
+  - https://github.com/kubernetes-sigs/kustomize//examples/helloWorld
+commonLabels:
+  app.kubernetes.io/name: orders-api
+  app.kubernetes.io/part-of: checkout
+"#,
+        )?;
+
+        let annotations = Kubernetes::default().get_annotations(test_dir.path())?;
+        assert_eq!(
+            annotations.get("backstage.io/kubernetes-id"),
+            Some(&"orders-api".to_string())
+        );
+        assert_eq!(
+            annotations.get("backstage.io/kubernetes-label-selector"),
+            Some(
+                &"app.kubernetes.io/name=orders-api,app.kubernetes.io/part-of=checkout".to_string()
+            )
+        );
+
+        let dependencies = Kubernetes::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(names, vec!["base-manifests"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_kustomization_file_names() -> Result<()> {
+        for file_name in ["kustomization.yml", "Kustomization"] {
+            let test_dir = tempdir()?;
+            std::fs::write(
+                test_dir.path().join(file_name),
+                r#"resources:
+  - https://bitbucket.org/bxbdigital/base-manifests//deploy?ref=v1.0.0
+commonLabels:
+  app.kubernetes.io/name: orders-api
+"#,
+            )?;
+
+            let annotations = Kubernetes::default().get_annotations(test_dir.path())?;
+            assert_eq!(
+                annotations.get("backstage.io/kubernetes-id"),
+                Some(&"orders-api".to_string())
+            );
+
+            let dependencies = Kubernetes::default().get_internal_dependencies(
+                test_dir.path(),
+                &InternalConfig::default().compile()?,
+            )?;
+            let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+            assert_eq!(names, vec!["base-manifests"]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_annotations_from_raw_manifests() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("deployment.yaml"), DEPLOYMENT)?;
+
+        let annotations = Kubernetes::default().get_annotations(test_dir.path())?;
+        assert_eq!(
+            annotations.get("backstage.io/kubernetes-id"),
+            Some(&"orders".to_string())
+        );
+        assert_eq!(
+            annotations.get("backstage.io/kubernetes-label-selector"),
+            Some(&"app.kubernetes.io/name=orders,team=checkout".to_string())
+        );
+
+        Ok(())
+    }
+}
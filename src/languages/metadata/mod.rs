@@ -0,0 +1,50 @@
+/// Metadata about a project read from its manifest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectMetadata {
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub authors: Vec<String>,
+    pub license: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub documentation: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+impl ProjectMetadata {
+    /// Fills in anything missing from `self` with the values from `other`, so metadata from the
+    /// primary language takes precedence.
+    pub fn merge(&mut self, other: ProjectMetadata) {
+        fn fill(value: &mut Option<String>, other: Option<String>) {
+            if value.is_none() {
+                *value = other.filter(|other| !other.trim().is_empty());
+            }
+        }
+
+        fill(&mut self.description, other.description);
+        fill(&mut self.version, other.version);
+        fill(&mut self.license, other.license);
+        fill(&mut self.homepage, other.homepage);
+        fill(&mut self.repository, other.repository);
+        fill(&mut self.documentation, other.documentation);
+
+        if self.authors.is_empty() {
+            self.authors = other.authors;
+        }
+        for keyword in other.keywords {
+            if !self.keywords.contains(&keyword) {
+                self.keywords.push(keyword);
+            }
+        }
+    }
+}
+
+/// Strips the email from an author written as `Name <email>`.
+pub fn author_name(author: &str) -> String {
+    author
+        .split('<')
+        .next()
+        .unwrap_or(author)
+        .trim()
+        .to_string()
+}
@@ -1,41 +1,171 @@
+pub mod component_type;
+pub mod detection;
+pub mod elixir;
 pub mod errors;
 pub mod go;
+pub mod helm;
+pub mod java;
+pub mod javascript;
+pub mod kubernetes;
+pub mod metadata;
+pub mod php;
+pub mod python;
+pub mod ruby;
 pub mod rust;
 // use crate::backstage::Component;
+use crate::config::InternalPatterns;
+use crate::lockfile::{Lockfile, ReadLockfileError};
+use component_type::ComponentTypeInference;
 // use std::error::Error;
 use crate::backstage::Component;
-use errors::GetInternalDependenciesError;
+use detection::Detection;
+use errors::{GetAnnotationsError, GetInternalDependenciesError, GetMetadataError};
+use metadata::ProjectMetadata;
+use std::collections::BTreeMap;
 use std::fmt::{Debug, Display};
 use std::path::Path;
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-pub trait Language: Dependencies + Display + Debug {}
+pub trait Language:
+    Detect + Dependencies + Annotations + Metadata + ComponentType + Display + Debug + Send + Sync
+{
+}
+
+pub trait Detect {
+    /// Describes the files that mark the root of a project in this language.
+    fn detection(&self) -> Detection;
+
+    /// Returns how confident we are that `dir`, containing `file_names`, is the root of a
+    /// project in this language.
+    fn detect(&self, dir: &Path, file_names: &[String]) -> Option<u8> {
+        self.detection().detect(dir, file_names)
+    }
+}
 
 pub trait Dependencies {
+    /// The dependencies on our own repositories and packages, as decided by `internal`.
     fn get_internal_dependencies(
         &self,
         project_root: &Path,
+        internal: &InternalPatterns,
     ) -> Result<Vec<Component>, GetInternalDependenciesError>;
-    fn get_project_file_name(&self) -> String;
+
+    /// Every dependency the project declares, internal or not, named as it is published.
+    /// Languages that can't list them all fall back to their internal dependencies.
+    fn get_all_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        self.get_internal_dependencies(project_root, internal)
+    }
+
+    /// The package URL type of the language's packages, e.g. `cargo` or `npm`.
+    fn purl_type(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Reads the lockfile pinning the exact versions of the project's dependencies, if the
+    /// language has one.
+    fn get_lockfile(&self, _project_root: &Path) -> Result<Option<Lockfile>, ReadLockfileError> {
+        Ok(None)
+    }
+}
+
+pub trait Annotations {
+    /// Returns the Backstage annotations that can be derived from the project's files.
+    fn get_annotations(
+        &self,
+        _project_root: &Path,
+    ) -> Result<BTreeMap<String, String>, GetAnnotationsError> {
+        Ok(BTreeMap::new())
+    }
+}
+
+pub trait Metadata {
+    /// Returns the description, version, authors and other metadata declared in the project's
+    /// manifest.
+    fn get_metadata(&self, _project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        Ok(ProjectMetadata::default())
+    }
+
+    /// Returns the names the project is published under, such as its Go module path, crate
+    /// name or npm package name, which other projects use to depend on it.
+    fn get_package_names(&self, _project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        Ok(vec![])
+    }
+}
+
+pub trait ComponentType {
+    /// Infers the Backstage component type of the project from its contents, returning `None`
+    /// when the language has no opinion.
+    fn infer_component_type(&self, _project_root: &Path) -> Option<ComponentTypeInference> {
+        None
+    }
 }
 
 #[derive(Debug, EnumIter)]
 pub enum Languages {
+    Elixir(elixir::Elixir),
     Go(go::Go),
+    Helm(helm::Helm),
+    Java(java::Java),
+    JavaScript(javascript::JavaScript),
+    Kubernetes(kubernetes::Kubernetes),
+    Php(php::Php),
+    Python(python::Python),
+    Ruby(ruby::Ruby),
     Rust(rust::Rust),
 }
 
 impl Languages {
-    pub fn get_project_file_name(&self) -> String {
+    pub fn detect(&self, dir: &Path, file_names: &[String]) -> Option<u8> {
         match self {
-            Languages::Go(lang) => lang.get_project_file_name(),
-            Languages::Rust(lang) => lang.get_project_file_name(),
+            Languages::Elixir(lang) => lang.detect(dir, file_names),
+            Languages::Go(lang) => lang.detect(dir, file_names),
+            Languages::Helm(lang) => lang.detect(dir, file_names),
+            Languages::Java(lang) => lang.detect(dir, file_names),
+            Languages::JavaScript(lang) => lang.detect(dir, file_names),
+            Languages::Kubernetes(lang) => lang.detect(dir, file_names),
+            Languages::Php(lang) => lang.detect(dir, file_names),
+            Languages::Python(lang) => lang.detect(dir, file_names),
+            Languages::Ruby(lang) => lang.detect(dir, file_names),
+            Languages::Rust(lang) => lang.detect(dir, file_names),
         }
     }
 
+    /// Finds the languages of the project rooted at `dir`, ordered from the most to the least
+    /// confident, with ties going to the language listed first.
+    pub fn detect_languages(dir: &Path, file_names: &[String]) -> Vec<Box<dyn Language>> {
+        let mut detected: Vec<(u8, Languages)> = Languages::iter()
+            .filter_map(|language| {
+                language
+                    .detect(dir, file_names)
+                    .map(|confidence| (confidence, language))
+            })
+            .collect();
+
+        // the sort is stable so equally confident languages keep their order
+        detected.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        detected
+            .into_iter()
+            .map(|(_, language)| language.get_language())
+            .collect()
+    }
+
     pub fn get_language(&self) -> Box<dyn Language> {
         match self {
+            Languages::Elixir(lang) => Box::new(lang.clone()),
             Languages::Go(lang) => Box::new(lang.clone()),
+            Languages::Helm(lang) => Box::new(lang.clone()),
+            Languages::Java(lang) => Box::new(lang.clone()),
+            Languages::JavaScript(lang) => Box::new(lang.clone()),
+            Languages::Kubernetes(lang) => Box::new(lang.clone()),
+            Languages::Php(lang) => Box::new(lang.clone()),
+            Languages::Python(lang) => Box::new(lang.clone()),
+            Languages::Ruby(lang) => Box::new(lang.clone()),
             Languages::Rust(lang) => Box::new(lang.clone()),
         }
     }
@@ -0,0 +1,329 @@
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::{ComponentTypeInference, depends_on_any};
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::ProjectMetadata;
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const PROJECT_FILE: &str = "composer.json";
+
+#[derive(Debug, Clone, Default)]
+pub struct Php {}
+
+/// The parts of a `composer.json` file that we care about.
+#[derive(Deserialize, Debug)]
+struct ComposerJson {
+    #[serde(default)]
+    require: BTreeMap<String, String>,
+
+    #[serde(default, rename = "require-dev")]
+    require_dev: BTreeMap<String, String>,
+
+    /// Either a list of repositories or a map of repository names to repositories
+    #[serde(default)]
+    repositories: serde_json::Value,
+
+    description: Option<String>,
+    version: Option<String>,
+    homepage: Option<String>,
+
+    /// Either a single license or a list of licenses
+    #[serde(default)]
+    license: serde_json::Value,
+
+    #[serde(default)]
+    keywords: Vec<String>,
+
+    #[serde(default)]
+    authors: Vec<ComposerAuthor>,
+
+    #[serde(default)]
+    support: BTreeMap<String, String>,
+}
+
+/// An author listed in a `composer.json` file.
+#[derive(Deserialize, Debug)]
+struct ComposerAuthor {
+    name: String,
+}
+
+/// A package repository declared in a `composer.json` file.
+#[derive(Deserialize, Debug)]
+struct ComposerRepository {
+    #[serde(default, rename = "type")]
+    kind: String,
+
+    #[serde(default)]
+    url: String,
+}
+
+impl Detect for Php {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![Marker::file(PROJECT_FILE, 100)],
+            exclusions: vec![],
+        }
+    }
+}
+
+impl Dependencies for Php {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let composer_json_path = project_root.join(PROJECT_FILE);
+        let composer_json_text =
+            std::fs::read_to_string(&composer_json_path).map_err(|source| {
+                GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+            })?;
+        let composer_json: ComposerJson =
+            serde_json::from_str(&composer_json_text).map_err(|source| {
+                GetInternalDependenciesError::FailedToParseProjectDependenciesJsonFile { source }
+            })?;
+
+        let repositories: Vec<serde_json::Value> = match composer_json.repositories {
+            serde_json::Value::Array(repositories) => repositories,
+            serde_json::Value::Object(repositories) => repositories.into_values().collect(),
+            _ => vec![],
+        };
+
+        // the names and URLs of our own repositories that packages are pulled from directly
+        let internal_vcs_repos: Vec<(String, String)> = repositories
+            .into_iter()
+            .filter_map(|repository| serde_json::from_value::<ComposerRepository>(repository).ok())
+            .filter(|repository| matches!(repository.kind.as_str(), "vcs" | "git"))
+            .filter_map(|repository| {
+                let repo_name = internal.repo_name(&repository.url)?.to_string();
+                Some((repo_name, repository.url))
+            })
+            .collect();
+
+        Ok(composer_json
+            .require
+            .iter()
+            .chain(&composer_json.require_dev)
+            .filter_map(|(package, version)| {
+                let package_name = package.split_once('/').map(|(_, name)| name)?;
+                let vcs_url = internal_vcs_repos
+                    .iter()
+                    .find(|(repo_name, _)| repo_name == package_name)
+                    .map(|(_, url)| url.clone());
+
+                if !internal.is_internal_vendor(package) && vcs_url.is_none() {
+                    return None;
+                }
+
+                // the vendor is kept, as packages are indexed under their full name
+                let component = Component::at(
+                    package,
+                    SourceLocation::find(
+                        &composer_json_path,
+                        &composer_json_text,
+                        &format!("\"{package}\""),
+                    ),
+                )
+                .with_version(version);
+                Some(match vcs_url {
+                    Some(url) => component.with_source(DependencySource::Git {
+                        url,
+                        reference: None,
+                    }),
+                    None => component.with_source(DependencySource::Registry { name: None }),
+                })
+            })
+            .collect())
+    }
+
+    fn get_all_dependencies(
+        &self,
+        project_root: &Path,
+        _internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let composer_json_path = project_root.join(PROJECT_FILE);
+        let composer_json_text =
+            std::fs::read_to_string(&composer_json_path).map_err(|source| {
+                GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+            })?;
+        let composer_json: ComposerJson =
+            serde_json::from_str(&composer_json_text).map_err(|source| {
+                GetInternalDependenciesError::FailedToParseProjectDependenciesJsonFile { source }
+            })?;
+
+        Ok(composer_json
+            .require
+            .iter()
+            .chain(&composer_json.require_dev)
+            // platform requirements such as `php` and `ext-json` are not packages
+            .filter(|(package, _)| package.contains('/'))
+            .map(|(package, version)| {
+                Component::at(
+                    package,
+                    SourceLocation::find(
+                        &composer_json_path,
+                        &composer_json_text,
+                        &format!("\"{package}\""),
+                    ),
+                )
+                .with_version(version)
+            })
+            .collect())
+    }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("composer")
+    }
+}
+
+impl Annotations for Php {}
+
+impl Metadata for Php {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let composer_json_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let composer_json: ComposerJson = serde_json::from_str(&composer_json_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectJsonManifest { source })?;
+
+        let license = match composer_json.license {
+            serde_json::Value::String(license) => Some(license),
+            serde_json::Value::Array(licenses) => licenses
+                .first()
+                .and_then(|license| license.as_str())
+                .map(|license| license.to_string()),
+            _ => None,
+        };
+
+        Ok(ProjectMetadata {
+            description: composer_json.description,
+            version: composer_json.version,
+            authors: composer_json
+                .authors
+                .into_iter()
+                .map(|author| author.name)
+                .collect(),
+            license,
+            homepage: composer_json.homepage,
+            repository: composer_json.support.get("source").cloned(),
+            documentation: composer_json.support.get("docs").cloned(),
+            keywords: composer_json.keywords,
+        })
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let composer_json_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let composer_json: serde_json::Value = serde_json::from_str(&composer_json_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectJsonManifest { source })?;
+
+        Ok(composer_json
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(|name| vec![name.to_string()])
+            .unwrap_or_default())
+    }
+}
+
+impl ComponentType for Php {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let composer_json_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok()?;
+        let composer_json: serde_json::Value = serde_json::from_str(&composer_json_text).ok()?;
+
+        let dependencies: Vec<String> = composer_json
+            .get("require")
+            .and_then(|require| require.as_object())
+            .map(|require| require.keys().cloned().collect())
+            .unwrap_or_default();
+
+        if depends_on_any(
+            &dependencies,
+            &[
+                "laravel/framework",
+                "symfony/framework-bundle",
+                "slim/slim",
+                "laminas/laminas-mvc",
+            ],
+        ) {
+            return Some(ComponentTypeInference::new("service", "php-web-framework"));
+        }
+
+        match composer_json.get("type").and_then(|kind| kind.as_str()) {
+            Some("project") => Some(ComponentTypeInference::new("service", "php-project")),
+            _ if composer_json.get("bin").is_some() => {
+                Some(ComponentTypeInference::new("cli", "php-bin"))
+            }
+            _ => Some(ComponentTypeInference::new("library", "php-library")),
+        }
+    }
+}
+
+impl Language for Php {}
+
+impl std::fmt::Display for Php {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "PHP")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("composer.json"),
+            r#"{
+    "name": "bxbdigital/storefront",
+    "repositories": [
+        { "type": "vcs", "url": "git@bitbucket.org:bxbdigital/pricing-sdk.git" },
+        { "type": "composer", "url": "https://packagist.org" }
+    ],
+    "require": {
+        "php": ">=8.2",
+        "acme/pricing-sdk": "^3.0",
+        "bxbdigital/auth-bundle": "^1.4",
+        "symfony/console": "^7.0"
+    },
+    "require-dev": {
+        "bxbdigital/coding-standard": "^2.0"
+    }
+}"#,
+        )?;
+
+        let dependencies = Php::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "acme/pricing-sdk",
+                "bxbdigital/auth-bundle",
+                "bxbdigital/coding-standard"
+            ]
+        );
+        assert_eq!(dependencies[0].version.as_deref(), Some("^3.0"));
+        assert_eq!(
+            dependencies[0].source,
+            Some(DependencySource::Git {
+                url: "git@bitbucket.org:bxbdigital/pricing-sdk.git".to_string(),
+                reference: None
+            })
+        );
+        assert_eq!(dependencies[1].version.as_deref(), Some("^1.4"));
+        assert_eq!(
+            dependencies[1].source,
+            Some(DependencySource::Registry { name: None })
+        );
+
+        Ok(())
+    }
+}
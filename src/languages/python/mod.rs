@@ -0,0 +1,615 @@
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, GitReference, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::{ComponentTypeInference, depends_on_any};
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::{ProjectMetadata, author_name};
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use crate::lockfile::{Lockfile, ReadLockfileError};
+use regex::Regex;
+use std::path::Path;
+
+const PROJECT_FILE: &str = "pyproject.toml";
+
+#[derive(Debug, Clone, Default)]
+pub struct Python {}
+
+/// Reads a string out of a TOML table.
+fn string(table: &toml::Table, key: &str) -> Option<String> {
+    table
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+/// Reads a list of strings out of a TOML table.
+fn strings(table: &toml::Table, key: &str) -> Vec<String> {
+    table
+        .get(key)
+        .and_then(|value| value.as_array())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str())
+                .map(|value| value.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Normalises a Python package name as described in PEP 503.
+fn normalise_package_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Works out where a PEP 508 direct reference fetches the package from, e.g.
+/// `git+https://bitbucket.org/bxbdigital/ledger.git@v1.2.0`, along with the version its git
+/// reference pins.
+fn direct_reference_source(url: &str) -> Option<(Option<String>, DependencySource)> {
+    let url = url.trim();
+    if let Some(path) = url.strip_prefix("file://") {
+        let source = DependencySource::Path {
+            path: path.to_string(),
+        };
+        return Some((None, source));
+    }
+
+    let url = url.strip_prefix("git+")?;
+    // the reference follows the last `@` of the path, not the one of `git@host`
+    let (url, reference) = match url.rsplit_once('@') {
+        Some((repo, reference)) if !reference.contains('/') => (repo, Some(reference)),
+        _ => (url, None),
+    };
+    let reference = reference.map(|reference| {
+        if reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit()) {
+            GitReference::Rev(reference.to_string())
+        } else {
+            GitReference::Tag(reference.to_string())
+        }
+    });
+    let version = reference
+        .as_ref()
+        .and_then(|reference| reference.version())
+        .map(|version| version.to_string());
+    let source = DependencySource::Git {
+        url: url.to_string(),
+        reference,
+    };
+    Some((version, source))
+}
+
+impl Detect for Python {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![
+                Marker::content(PROJECT_FILE, r#"(?m)^\[(project|tool\.poetry)\]"#, 100),
+                Marker::file("setup.py", 80),
+            ],
+            exclusions: vec![],
+        }
+    }
+}
+
+impl Dependencies for Python {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let requirement_regex = Regex::new(r#"^\s*([A-Za-z0-9_.\-]+)\s*(\[[^\]]*\])?\s*@\s*(.+)$"#)
+            .map_err(
+                |source| GetInternalDependenciesError::FailedToCompileDependencyRegex { source },
+            )?;
+
+        // projects with only a setup.py have nothing we can read reliably
+        let pyproject_path = project_root.join(PROJECT_FILE);
+        if !pyproject_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let pyproject_text = std::fs::read_to_string(&pyproject_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let pyproject: toml::Table = toml::from_str(&pyproject_text).map_err(|source| {
+            GetInternalDependenciesError::FailedToParseProjectDependenciesTomlFile { source }
+        })?;
+
+        let mut requirements: Vec<(String, Option<String>, Option<DependencySource>)> = vec![];
+
+        // PEP 508 direct references, e.g. `ledger @ git+https://bitbucket.org/...`
+        if let Some(project) = pyproject
+            .get("project")
+            .and_then(|project| project.as_table())
+        {
+            for requirement in strings(project, "dependencies") {
+                if let Some(captures) = requirement_regex.captures(&requirement)
+                    && internal.is_internal_source(captures.get(3).map_or("", |m| m.as_str()))
+                {
+                    let (version, source) =
+                        direct_reference_source(captures.get(3).map_or("", |m| m.as_str())).unzip();
+                    requirements.push((
+                        captures.get(1).map_or("", |m| m.as_str()).to_string(),
+                        version.flatten(),
+                        source,
+                    ));
+                }
+            }
+        }
+
+        // poetry dependencies from our repositories or our private package sources
+        if let Some(poetry) = pyproject
+            .get("tool")
+            .and_then(|tool| tool.get("poetry"))
+            .and_then(|poetry| poetry.as_table())
+        {
+            let internal_sources: Vec<String> = poetry
+                .get("source")
+                .and_then(|sources| sources.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|source| source.as_table())
+                .filter(|source| {
+                    string(source, "url").is_some_and(|url| internal.is_internal_source(&url))
+                })
+                .filter_map(|source| string(source, "name"))
+                .collect();
+
+            let dependency_tables = ["dependencies", "dev-dependencies"]
+                .into_iter()
+                .filter_map(|key| poetry.get(key).and_then(|table| table.as_table()))
+                .chain(
+                    poetry
+                        .get("group")
+                        .and_then(|groups| groups.as_table())
+                        .into_iter()
+                        .flat_map(|groups| groups.values())
+                        .filter_map(|group| group.get("dependencies"))
+                        .filter_map(|table| table.as_table()),
+                );
+
+            for dependencies in dependency_tables {
+                for (name, specification) in dependencies {
+                    let Some(specification) = specification.as_table() else {
+                        continue;
+                    };
+
+                    let git =
+                        string(specification, "git").filter(|git| internal.is_internal_repo(git));
+                    let package_source = string(specification, "source")
+                        .filter(|source| internal_sources.contains(source));
+
+                    if let Some(url) = git {
+                        let reference = string(specification, "rev")
+                            .map(GitReference::Rev)
+                            .or_else(|| string(specification, "tag").map(GitReference::Tag))
+                            .or_else(|| string(specification, "branch").map(GitReference::Branch));
+                        let version = reference
+                            .as_ref()
+                            .and_then(|reference| reference.version())
+                            .map(|version| version.to_string());
+                        let source = DependencySource::Git { url, reference };
+                        requirements.push((name.clone(), version, Some(source)));
+                    } else if let Some(package_source) = package_source {
+                        let source = DependencySource::Registry {
+                            name: Some(package_source),
+                        };
+                        requirements.push((
+                            name.clone(),
+                            string(specification, "version"),
+                            Some(source),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut components: Vec<Component> = vec![];
+        for (name, version, source) in requirements {
+            let normalised_name = normalise_package_name(&name);
+            if components
+                .iter()
+                .any(|component| component.name == normalised_name)
+            {
+                continue;
+            }
+
+            let mut component = Component::at(
+                &normalised_name,
+                SourceLocation::find(&pyproject_path, &pyproject_text, &name),
+            );
+            if let Some(version) = version {
+                component = component.with_version(version.trim_start_matches("=="));
+            }
+            if let Some(source) = source {
+                component = component.with_source(source);
+            }
+            components.push(component);
+        }
+
+        Ok(components)
+    }
+
+    fn get_all_dependencies(
+        &self,
+        project_root: &Path,
+        _internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        // e.g. `ledger[cli] >=1.2,<2` or `ledger @ git+https://...`
+        let requirement_regex = Regex::new(r#"^\s*([A-Za-z0-9_.\-]+)\s*(\[[^\]]*\])?\s*([^;]*)"#)
+            .map_err(|source| {
+            GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+        })?;
+
+        let pyproject_path = project_root.join(PROJECT_FILE);
+        if !pyproject_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let pyproject_text = std::fs::read_to_string(&pyproject_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let pyproject: toml::Table = toml::from_str(&pyproject_text).map_err(|source| {
+            GetInternalDependenciesError::FailedToParseProjectDependenciesTomlFile { source }
+        })?;
+
+        let mut requirements: Vec<(String, Option<String>)> = vec![];
+        if let Some(project) = pyproject
+            .get("project")
+            .and_then(|project| project.as_table())
+        {
+            for requirement in strings(project, "dependencies") {
+                if let Some(captures) = requirement_regex.captures(&requirement) {
+                    let specifier = captures.get(3).map_or("", |m| m.as_str()).trim();
+                    requirements.push((
+                        captures.get(1).map_or("", |m| m.as_str()).to_string(),
+                        (!specifier.is_empty()).then(|| specifier.to_string()),
+                    ));
+                }
+            }
+        }
+        if let Some(poetry) = pyproject
+            .get("tool")
+            .and_then(|tool| tool.get("poetry"))
+            .and_then(|poetry| poetry.as_table())
+        {
+            for dependencies in ["dependencies", "dev-dependencies"]
+                .into_iter()
+                .filter_map(|key| poetry.get(key).and_then(|table| table.as_table()))
+            {
+                for (name, specification) in dependencies {
+                    // the python version is a constraint on the interpreter, not a package
+                    if name == "python" {
+                        continue;
+                    }
+                    let version = match specification {
+                        toml::Value::String(version) => Some(version.clone()),
+                        toml::Value::Table(specification) => string(specification, "version"),
+                        _ => None,
+                    };
+                    requirements.push((name.clone(), version));
+                }
+            }
+        }
+
+        let mut components: Vec<Component> = vec![];
+        for (name, version) in requirements {
+            let normalised_name = normalise_package_name(&name);
+            if components
+                .iter()
+                .any(|component| component.name == normalised_name)
+            {
+                continue;
+            }
+
+            let component = Component::at(
+                &normalised_name,
+                SourceLocation::find(&pyproject_path, &pyproject_text, &name),
+            );
+            components.push(match version {
+                Some(version) => component.with_version(version.trim_start_matches("==")),
+                None => component,
+            });
+        }
+
+        Ok(components)
+    }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("pypi")
+    }
+
+    fn get_lockfile(&self, project_root: &Path) -> Result<Option<Lockfile>, ReadLockfileError> {
+        Lockfile::find(project_root, &["poetry.lock"])
+    }
+}
+
+impl Annotations for Python {}
+
+impl Metadata for Python {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let pyproject_path = project_root.join(PROJECT_FILE);
+        if !pyproject_path.exists() {
+            return Ok(ProjectMetadata::default());
+        }
+
+        let pyproject_text = std::fs::read_to_string(pyproject_path)
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let pyproject: toml::Table = toml::from_str(&pyproject_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectTomlManifest { source })?;
+
+        let mut metadata = ProjectMetadata::default();
+
+        // PEP 621 metadata
+        if let Some(project) = pyproject
+            .get("project")
+            .and_then(|project| project.as_table())
+        {
+            let urls = project
+                .get("urls")
+                .and_then(|urls| urls.as_table())
+                .cloned()
+                .unwrap_or_default();
+            let url = |names: &[&str]| {
+                urls.iter()
+                    .find(|(name, _)| names.contains(&name.to_lowercase().as_str()))
+                    .and_then(|(_, url)| url.as_str())
+                    .map(|url| url.to_string())
+            };
+
+            metadata.merge(ProjectMetadata {
+                description: string(project, "description"),
+                version: string(project, "version"),
+                authors: project
+                    .get("authors")
+                    .and_then(|authors| authors.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|author| author.get("name"))
+                    .filter_map(|name| name.as_str())
+                    .map(|name| name.to_string())
+                    .collect(),
+                license: project.get("license").and_then(|license| {
+                    license
+                        .as_str()
+                        .or_else(|| license.get("text").and_then(|text| text.as_str()))
+                        .map(|license| license.to_string())
+                }),
+                homepage: url(&["homepage", "home"]),
+                repository: url(&["repository", "source", "source code"]),
+                documentation: url(&["documentation", "docs"]),
+                keywords: strings(project, "keywords"),
+            });
+        }
+
+        // poetry metadata
+        if let Some(poetry) = pyproject
+            .get("tool")
+            .and_then(|tool| tool.get("poetry"))
+            .and_then(|poetry| poetry.as_table())
+        {
+            metadata.merge(ProjectMetadata {
+                description: string(poetry, "description"),
+                version: string(poetry, "version"),
+                authors: strings(poetry, "authors")
+                    .iter()
+                    .map(|author| author_name(author))
+                    .collect(),
+                license: string(poetry, "license"),
+                homepage: string(poetry, "homepage"),
+                repository: string(poetry, "repository"),
+                documentation: string(poetry, "documentation"),
+                keywords: strings(poetry, "keywords"),
+            });
+        }
+
+        Ok(metadata)
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let pyproject_path = project_root.join(PROJECT_FILE);
+        if !pyproject_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let pyproject_text = std::fs::read_to_string(pyproject_path)
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let pyproject: toml::Table = toml::from_str(&pyproject_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectTomlManifest { source })?;
+
+        let name = pyproject
+            .get("project")
+            .and_then(|project| project.get("name"))
+            .or_else(|| {
+                pyproject
+                    .get("tool")
+                    .and_then(|tool| tool.get("poetry"))
+                    .and_then(|poetry| poetry.get("name"))
+            })
+            .and_then(|name| name.as_str());
+
+        Ok(name
+            .map(|name| vec![normalise_package_name(name)])
+            .unwrap_or_default())
+    }
+}
+
+impl ComponentType for Python {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let pyproject_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok()?;
+        let pyproject: toml::Table = toml::from_str(&pyproject_text).ok()?;
+
+        let project = pyproject
+            .get("project")
+            .and_then(|project| project.as_table());
+        let poetry = pyproject
+            .get("tool")
+            .and_then(|tool| tool.get("poetry"))
+            .and_then(|poetry| poetry.as_table());
+
+        let mut dependencies: Vec<String> = project
+            .map(|project| strings(project, "dependencies"))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|requirement| {
+                requirement
+                    .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+                    .next()
+            })
+            .map(normalise_package_name)
+            .collect();
+        if let Some(poetry_dependencies) = poetry
+            .and_then(|poetry| poetry.get("dependencies"))
+            .and_then(|dependencies| dependencies.as_table())
+        {
+            dependencies.extend(
+                poetry_dependencies
+                    .keys()
+                    .map(|name| normalise_package_name(name)),
+            );
+        }
+
+        if depends_on_any(
+            &dependencies,
+            &[
+                "fastapi",
+                "flask",
+                "django",
+                "aiohttp",
+                "starlette",
+                "tornado",
+                "sanic",
+            ],
+        ) {
+            return Some(ComponentTypeInference::new(
+                "service",
+                "python-web-framework",
+            ));
+        }
+
+        let has_scripts = project.is_some_and(|project| project.contains_key("scripts"))
+            || poetry.is_some_and(|poetry| poetry.contains_key("scripts"));
+        if has_scripts {
+            return Some(ComponentTypeInference::new("cli", "python-scripts"));
+        }
+
+        Some(ComponentTypeInference::new("library", "python-package"))
+    }
+}
+
+impl Language for Python {}
+
+impl std::fmt::Display for Python {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Python")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_internal_dependencies_direct_references() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("pyproject.toml"),
+            r#"[project]
+name = "pricing-engine"
+dependencies = [
+    "ledger @ git+ssh://git@bitbucket.org/bxbdigital/ledger.git@v2.0.1",
+    "audit @ git+https://bitbucket.org/bxbdigital/audit.git",
+    "requests>=2.31",
+]
+"#,
+        )?;
+
+        let dependencies = Python::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let summary: Vec<(&str, Option<&str>, String)> = dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.name.as_str(),
+                    dependency.version.as_deref(),
+                    dependency
+                        .source
+                        .as_ref()
+                        .map(|source| source.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "ledger",
+                    Some("v2.0.1"),
+                    "git:ssh://git@bitbucket.org/bxbdigital/ledger.git#tag=v2.0.1".to_string()
+                ),
+                (
+                    "audit",
+                    None,
+                    "git:https://bitbucket.org/bxbdigital/audit.git".to_string()
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("pyproject.toml"),
+            r#"[tool.poetry]
+name = "pricing-engine"
+version = "0.3.0"
+description = "Calculates prices"
+authors = ["Jane Doe <jane@example.com>"]
+
+[tool.poetry.dependencies]
+python = "^3.11"
+requests = "^2.31"
+Ledger_Client = { git = "https://bitbucket.org/bxbdigital/ledger-client.git", tag = "v1.2.0" }
+feature-flags = { version = "^2.0", source = "internal" }
+
+[[tool.poetry.source]]
+name = "internal"
+url = "https://pypi.bxbdigital.io/simple"
+priority = "supplemental"
+"#,
+        )?;
+
+        let dependencies = Python::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let names: Vec<&str> = dependencies.iter().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(names, vec!["ledger-client", "feature-flags"]);
+        assert_eq!(dependencies[0].version.as_deref(), Some("v1.2.0"));
+        assert_eq!(
+            dependencies[0].source,
+            Some(DependencySource::Git {
+                url: "https://bitbucket.org/bxbdigital/ledger-client.git".to_string(),
+                reference: Some(GitReference::Tag("v1.2.0".to_string())),
+            })
+        );
+        assert_eq!(dependencies[1].version.as_deref(), Some("^2.0"));
+        assert_eq!(
+            dependencies[1].source,
+            Some(DependencySource::Registry {
+                name: Some("internal".to_string())
+            })
+        );
+
+        let metadata = Python::default().get_metadata(test_dir.path())?;
+        assert_eq!(metadata.description, Some("Calculates prices".to_string()));
+        assert_eq!(metadata.authors, vec!["Jane Doe".to_string()]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,383 @@
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, GitReference, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::ComponentTypeInference;
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::ProjectMetadata;
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Language, Metadata};
+use regex::Regex;
+use std::path::Path;
+
+const PROJECT_FILE: &str = "Gemfile";
+
+#[derive(Debug, Clone, Default)]
+pub struct Ruby {}
+
+impl Detect for Ruby {
+    fn detection(&self) -> Detection {
+        Detection {
+            markers: vec![
+                Marker::file(PROJECT_FILE, 100),
+                Marker::file("*.gemspec", 90),
+            ],
+            exclusions: vec![],
+        }
+    }
+}
+
+impl Dependencies for Ruby {
+    fn get_internal_dependencies(
+        &self,
+        project_root: &Path,
+        internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let gem_regex = Regex::new(r#"^\s*gem\s+['"]([^'"]+)['"](.*)$"#).map_err(|source| {
+            GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+        })?;
+        let source_block_regex = Regex::new(r#"^\s*source\s+\(?['"]([^'"]+)['"]\)?\s+do\b"#)
+            .map_err(
+                |source| GetInternalDependenciesError::FailedToCompileDependencyRegex { source },
+            )?;
+        let gem_source_regex =
+            Regex::new(r#":?(git|github|bitbucket|source|path)(:|\s*=>)\s*['"]([^'"]+)['"]"#)
+                .map_err(
+                    |source| GetInternalDependenciesError::FailedToCompileDependencyRegex {
+                        source,
+                    },
+                )?;
+        let gem_reference_regex = Regex::new(r#":?(ref|tag|branch)(:|\s*=>)\s*['"]([^'"]+)['"]"#)
+            .map_err(|source| {
+            GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+        })?;
+        // the version requirement is the first argument after the gem's name
+        let gem_version_regex = Regex::new(r#"^\s*,\s*['"]([^'"]+)['"]"#).map_err(|source| {
+            GetInternalDependenciesError::FailedToCompileDependencyRegex { source }
+        })?;
+
+        // a gemspec does not say where its dependencies come from, only the Gemfile does
+        let gemfile_path = project_root.join(PROJECT_FILE);
+        if !gemfile_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let gemfile_text = std::fs::read_to_string(&gemfile_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+
+        let mut components: Vec<Component> = vec![];
+
+        // the gem source of each open `do` block, so gems inside a private source block
+        // are recognised
+        let mut block_sources: Vec<Option<String>> = vec![];
+
+        for (line_index, line) in gemfile_text.lines().enumerate() {
+            let line = line.split(" #").next().unwrap_or(line);
+
+            if let Some(source_block) = source_block_regex.captures(line) {
+                block_sources.push(source_block.get(1).map(|m| m.as_str().to_string()));
+                continue;
+            }
+
+            if line.trim() == "end" {
+                block_sources.pop();
+                continue;
+            }
+
+            if let Some(gem) = gem_regex.captures(line) {
+                let name = gem.get(1).map_or("", |m| m.as_str());
+                let options = gem.get(2).map_or("", |m| m.as_str());
+
+                let reference = gem_reference_regex.captures(options).map(|reference| {
+                    let name = reference.get(3).map_or("", |m| m.as_str()).to_string();
+                    match reference.get(1).map_or("", |m| m.as_str()) {
+                        "ref" => GitReference::Rev(name),
+                        "tag" => GitReference::Tag(name),
+                        _ => GitReference::Branch(name),
+                    }
+                });
+
+                let gem_source = gem_source_regex
+                    .captures_iter(options)
+                    .find_map(|gem_source| {
+                        let kind = gem_source.get(1).map_or("", |m| m.as_str());
+                        let location = gem_source.get(3).map_or("", |m| m.as_str());
+                        match kind {
+                            "path" => Some(DependencySource::Path {
+                                path: location.to_string(),
+                            }),
+                            // shorthands for `org/repo` on GitHub or Bitbucket
+                            "github" | "bitbucket" => location
+                                .split_once('/')
+                                .is_some_and(|(org, _)| internal.is_internal_org(org))
+                                .then(|| {
+                                    let host = match kind {
+                                        "github" => "github.com",
+                                        _ => "bitbucket.org",
+                                    };
+                                    DependencySource::Git {
+                                        url: format!("https://{host}/{location}.git"),
+                                        reference: reference.clone(),
+                                    }
+                                }),
+                            "git" => internal.is_internal_source(location).then(|| {
+                                DependencySource::Git {
+                                    url: location.to_string(),
+                                    reference: reference.clone(),
+                                }
+                            }),
+                            _ => internal.is_internal_source(location).then(|| {
+                                DependencySource::Registry {
+                                    name: Some(location.to_string()),
+                                }
+                            }),
+                        }
+                    });
+                let gem_source = gem_source.or_else(|| {
+                    block_sources
+                        .iter()
+                        .flatten()
+                        .find(|source| internal.is_internal_source(source))
+                        .map(|source| DependencySource::Registry {
+                            name: Some(source.clone()),
+                        })
+                });
+
+                if let Some(gem_source) = gem_source
+                    && !components.iter().any(|component| component.name == name)
+                {
+                    // git gems without a version requirement are pinned by their ref or tag
+                    let version = gem_version_regex
+                        .captures(options)
+                        .and_then(|version| version.get(1))
+                        .map(|version| version.as_str())
+                        .or(reference.as_ref().and_then(|reference| reference.version()));
+
+                    let component = Component::at(
+                        name,
+                        Some(SourceLocation {
+                            file: gemfile_path.clone(),
+                            line: line_index + 1,
+                        }),
+                    );
+                    let component = match version {
+                        Some(version) => component.with_version(version),
+                        None => component,
+                    };
+                    components.push(component.with_source(gem_source));
+                }
+            }
+
+            if line.trim_end().ends_with(" do") || line.contains(" do |") {
+                block_sources.push(None);
+            }
+        }
+
+        Ok(components)
+    }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("gem")
+    }
+}
+
+impl Annotations for Ruby {}
+
+impl Metadata for Ruby {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let field_regex = Regex::new(
+            r#"(?m)^\s*\w+\.(summary|description|homepage|version|licenses?)\s*=\s*\[?\s*['"]([^'"]+)['"]"#,
+        )
+        .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+        let uri_regex = Regex::new(
+            r#"metadata\[\s*['"](source_code_uri|documentation_uri)['"]\s*\]\s*=\s*['"]([^'"]+)['"]"#,
+        )
+        .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+        let authors_regex = Regex::new(r#"\.authors\s*=\s*\[([^\]]*)\]"#)
+            .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+        let quoted_regex = Regex::new(r#"['"]([^'"]+)['"]"#)
+            .map_err(|source| GetMetadataError::FailedToCompileMetadataRegex { source })?;
+
+        let mut gemspec_paths: Vec<_> = std::fs::read_dir(project_root)
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "gemspec")
+            })
+            .collect();
+        gemspec_paths.sort();
+
+        // only gems have a gemspec, applications have nothing to describe them
+        let Some(gemspec_path) = gemspec_paths.first() else {
+            return Ok(ProjectMetadata::default());
+        };
+        let gemspec_text = std::fs::read_to_string(gemspec_path)
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+
+        let mut metadata = ProjectMetadata::default();
+        let mut summary = None;
+        for field in field_regex.captures_iter(&gemspec_text) {
+            let value = field.get(2).map(|m| m.as_str().to_string());
+            match field.get(1).map_or("", |m| m.as_str()) {
+                "summary" => summary = value,
+                "description" => metadata.description = value,
+                "homepage" => metadata.homepage = value,
+                "version" => metadata.version = value,
+                _ => metadata.license = value,
+            }
+        }
+        if metadata.description.is_none() {
+            metadata.description = summary;
+        }
+
+        for uri in uri_regex.captures_iter(&gemspec_text) {
+            let value = uri.get(2).map(|m| m.as_str().to_string());
+            match uri.get(1).map_or("", |m| m.as_str()) {
+                "source_code_uri" => metadata.repository = value,
+                _ => metadata.documentation = value,
+            }
+        }
+
+        if let Some(authors) = authors_regex
+            .captures(&gemspec_text)
+            .and_then(|captures| captures.get(1))
+        {
+            metadata.authors = quoted_regex
+                .captures_iter(authors.as_str())
+                .filter_map(|author| author.get(1))
+                .map(|author| author.as_str().to_string())
+                .collect();
+        }
+
+        Ok(metadata)
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        // gemspecs are named after the gem they build
+        let mut names: Vec<String> = std::fs::read_dir(project_root)
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "gemspec")
+            })
+            .filter_map(|path| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+            })
+            .collect();
+        names.sort();
+
+        Ok(names)
+    }
+}
+
+impl ComponentType for Ruby {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let gemfile_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok();
+        let frameworks_regex =
+            Regex::new(r#"(?m)^\s*gem\s+['"](rails|sinatra|hanami|roda|grape)['"]"#).ok()?;
+
+        if gemfile_text
+            .as_deref()
+            .is_some_and(|gemfile_text| frameworks_regex.is_match(gemfile_text))
+        {
+            return Some(ComponentTypeInference::new("service", "ruby-web-framework"));
+        }
+
+        if project_root.join("exe").is_dir() || project_root.join("bin").is_dir() {
+            return Some(ComponentTypeInference::new("cli", "ruby-executables"));
+        }
+
+        Some(ComponentTypeInference::new("library", "ruby-gem"))
+    }
+}
+
+impl Language for Ruby {}
+
+impl std::fmt::Display for Ruby {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Ruby")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("Gemfile"),
+            r#"source "https://rubygems.org"
+
+gemspec
+
+gem "rails", "~> 7.1"
+gem "billing-client", git: "git@bitbucket.org:bxbdigital/billing-client.git", tag: "v2.0.1"
+gem 'audit_log', :git => 'https://bitbucket.org/bxbdigital/audit_log.git'
+gem "shared_models", path: "../shared_models"
+gem "pricing", github: "bxbdigital/pricing", branch: "main"
+gem "octokit", github: "octokit/octokit.rb"
+
+source "https://gems.bxbdigital.io/private" do
+  gem "feature_flags", "~> 3.2"
+end
+
+group :development, :test do
+  gem "rspec-rails"
+end
+"#,
+        )?;
+
+        let dependencies = Ruby::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let summary: Vec<(&str, Option<&str>, String)> = dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.name.as_str(),
+                    dependency.version.as_deref(),
+                    dependency
+                        .source
+                        .as_ref()
+                        .map(|source| source.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "billing-client",
+                    Some("v2.0.1"),
+                    "git:git@bitbucket.org:bxbdigital/billing-client.git#tag=v2.0.1".to_string()
+                ),
+                (
+                    "audit_log",
+                    None,
+                    "git:https://bitbucket.org/bxbdigital/audit_log.git".to_string()
+                ),
+                ("shared_models", None, "path:../shared_models".to_string()),
+                (
+                    "pricing",
+                    None,
+                    "git:https://github.com/bxbdigital/pricing.git#branch=main".to_string()
+                ),
+                (
+                    "feature_flags",
+                    Some("~> 3.2"),
+                    "registry:https://gems.bxbdigital.io/private".to_string()
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
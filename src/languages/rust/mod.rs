@@ -1,25 +1,333 @@
-use super::errors::GetInternalDependenciesError;
-use crate::backstage::Component;
-use crate::languages::Dependencies;
+use super::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::backstage::{Component, DependencySource, GitReference, SourceLocation};
+use crate::config::InternalPatterns;
+use crate::languages::component_type::{ComponentTypeInference, depends_on_any};
+use crate::languages::detection::{Detection, Marker};
+use crate::languages::metadata::{ProjectMetadata, author_name};
+use crate::languages::{Annotations, ComponentType, Dependencies, Detect, Metadata};
+use crate::lockfile::{Lockfile, ReadLockfileError};
+use regex::Regex;
 use std::path::Path;
 
+const PROJECT_FILE: &str = "Cargo.toml";
+
 #[derive(Debug, Clone, Default)]
 pub struct Rust {}
 
-impl Dependencies for Rust {
-    fn get_project_file_name(&self) -> String {
-        "Cargo.toml".to_string()
+impl Detect for Rust {
+    fn detection(&self) -> Detection {
+        // a Cargo.toml with only a [workspace] table is not a crate of its own
+        Detection {
+            markers: vec![Marker::content(PROJECT_FILE, r#"(?m)^\[package\]"#, 100)],
+            exclusions: vec![],
+        }
     }
+}
 
+impl Dependencies for Rust {
+    /// Internal dependencies are those fetched from one of our git repositories, from one of our
+    /// registries or from a path, i.e. another crate of the same repository.
     fn get_internal_dependencies(
         &self,
-        _project_root: &Path,
+        project_root: &Path,
+        internal: &InternalPatterns,
     ) -> Result<Vec<Component>, GetInternalDependenciesError> {
-        let components = vec![];
-        let _internal_repo_regex = r#".*bitbucket.org[\/:]bxbdigital\/.*"#;
+        Ok(self
+            .get_all_dependencies(project_root, internal)?
+            .into_iter()
+            .filter(|component| match &component.source {
+                Some(DependencySource::Git { url, .. }) => internal.is_internal_repo(url),
+                Some(DependencySource::Path { .. }) => true,
+                Some(DependencySource::Registry { name: Some(name) }) => {
+                    internal.is_internal_org(name) || internal.is_internal_source(name)
+                }
+                Some(DependencySource::Registry { name: None }) => false,
+                None => false,
+            })
+            .collect())
+    }
+
+    fn get_all_dependencies(
+        &self,
+        project_root: &Path,
+        _internal: &InternalPatterns,
+    ) -> Result<Vec<Component>, GetInternalDependenciesError> {
+        let cargo_toml_path = project_root.join(PROJECT_FILE);
+        let cargo_toml_text = std::fs::read_to_string(&cargo_toml_path).map_err(|source| {
+            GetInternalDependenciesError::FailedToReadProjectDependenciesFile { source }
+        })?;
+        let cargo_toml: toml::Table = toml::from_str(&cargo_toml_text).map_err(|source| {
+            GetInternalDependenciesError::FailedToParseProjectDependenciesTomlFile { source }
+        })?;
+
+        let workspace_dependencies = find_workspace_dependencies(project_root);
+
+        // dependencies can also be declared per target, e.g. [target.'cfg(unix)'.dependencies]
+        let mut tables = vec![&cargo_toml];
+        tables.extend(
+            cargo_toml
+                .get("target")
+                .and_then(|targets| targets.as_table())
+                .into_iter()
+                .flat_map(|targets| targets.values())
+                .filter_map(|target| target.as_table()),
+        );
+
+        let mut components: Vec<Component> = vec![];
+        for (key, dependency) in tables
+            .into_iter()
+            .flat_map(|table| {
+                ["dependencies", "dev-dependencies", "build-dependencies"]
+                    .into_iter()
+                    .filter_map(|kind| table.get(kind))
+            })
+            .filter_map(|dependencies| dependencies.as_table())
+            .flatten()
+        {
+            let location = find_dependency(&cargo_toml_path, &cargo_toml_text, key);
+            let dependency = inherit_workspace_dependency(key, dependency, &workspace_dependencies);
+
+            // plain version requirements come from crates.io
+            let Some(dependency) = dependency.as_table() else {
+                if !components.iter().any(|component| &component.name == key) {
+                    let component = Component::at(key, location)
+                        .with_source(DependencySource::Registry { name: None });
+                    components.push(match dependency.as_str() {
+                        Some(version) => component.with_version(version),
+                        None => component,
+                    });
+                }
+                continue;
+            };
+            let string = |key: &str| dependency.get(key).and_then(|value| value.as_str());
+
+            let source = if let Some(url) = string("git") {
+                let reference = string("rev")
+                    .map(|rev| GitReference::Rev(rev.to_string()))
+                    .or_else(|| string("tag").map(|tag| GitReference::Tag(tag.to_string())))
+                    .or_else(|| {
+                        string("branch").map(|branch| GitReference::Branch(branch.to_string()))
+                    });
+                DependencySource::Git {
+                    url: url.to_string(),
+                    reference,
+                }
+            } else if let Some(path) = string("path") {
+                DependencySource::Path {
+                    path: path.to_string(),
+                }
+            } else {
+                DependencySource::Registry {
+                    name: string("registry")
+                        .or(string("registry-index"))
+                        .map(|registry| registry.to_string()),
+                }
+            };
+
+            // a dependency can be renamed, e.g. `ledger_v1 = { package = "ledger" }`
+            let name = string("package").unwrap_or(key);
+            if components.iter().any(|component| component.name == name) {
+                continue;
+            }
+
+            let mut component = Component::at(name, location);
+            // git dependencies without a version requirement are pinned by their rev or tag
+            let version = string("version").or(match &source {
+                DependencySource::Git {
+                    reference: Some(reference),
+                    ..
+                } => reference.version(),
+                _ => None,
+            });
+            if let Some(version) = version {
+                component = component.with_version(version);
+            }
+            components.push(component.with_source(source));
+        }
 
         Ok(components)
     }
+
+    fn purl_type(&self) -> Option<&'static str> {
+        Some("cargo")
+    }
+
+    fn get_lockfile(&self, project_root: &Path) -> Result<Option<Lockfile>, ReadLockfileError> {
+        Lockfile::find(project_root, &["Cargo.lock"])
+    }
+}
+
+/// Reads the `[workspace.dependencies]` of the workspace the crate belongs to, which is the
+/// closest Cargo.toml with a `[workspace]` table, stopping at the repository root.
+fn find_workspace_dependencies(project_root: &Path) -> toml::Table {
+    for directory in project_root.ancestors() {
+        let workspace = std::fs::read_to_string(directory.join(PROJECT_FILE))
+            .ok()
+            .and_then(|text| toml::from_str::<toml::Table>(&text).ok())
+            .and_then(|cargo_toml| cargo_toml.get("workspace").cloned());
+        if let Some(workspace) = workspace {
+            return workspace
+                .get("dependencies")
+                .and_then(|dependencies| dependencies.as_table())
+                .cloned()
+                .unwrap_or_default();
+        }
+        if directory.join(".git").exists() {
+            break;
+        }
+    }
+    toml::Table::new()
+}
+
+/// Replaces a `key = { workspace = true }` dependency with the workspace's declaration of
+/// `key`, keeping the keys the crate adds to it, such as `features`.
+fn inherit_workspace_dependency(
+    key: &str,
+    dependency: &toml::Value,
+    workspace_dependencies: &toml::Table,
+) -> toml::Value {
+    let inherits = dependency
+        .get("workspace")
+        .and_then(|workspace| workspace.as_bool())
+        .unwrap_or(false);
+    let Some(inherited) = workspace_dependencies.get(key).filter(|_| inherits) else {
+        return dependency.clone();
+    };
+
+    let mut merged = match inherited {
+        toml::Value::String(version) => {
+            toml::Table::from_iter([("version".to_string(), toml::Value::String(version.clone()))])
+        }
+        toml::Value::Table(inherited) => inherited.clone(),
+        _ => toml::Table::new(),
+    };
+    for (name, value) in dependency.as_table().into_iter().flatten() {
+        if name != "workspace" {
+            merged.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    toml::Value::Table(merged)
+}
+
+/// Finds the line declaring the dependency `key`, either as `key = ...` within a dependencies
+/// table or as a `[dependencies.key]` table of its own.
+fn find_dependency(path: &Path, text: &str, key: &str) -> Option<SourceLocation> {
+    let key = regex::escape(key);
+    let dependency_regex =
+        Regex::new(&format!(r#"(?m)^(?:{key}\s*=|\[.*dependencies\.{key}\])"#)).ok()?;
+    dependency_regex
+        .find(text)
+        .map(|dependency| SourceLocation::from_offset(path, text, dependency.start()))
+}
+
+impl Annotations for Rust {}
+
+impl Metadata for Rust {
+    fn get_metadata(&self, project_root: &Path) -> Result<ProjectMetadata, GetMetadataError> {
+        let cargo_toml_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let cargo_toml: toml::Table = toml::from_str(&cargo_toml_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectTomlManifest { source })?;
+
+        let Some(package) = cargo_toml
+            .get("package")
+            .and_then(|package| package.as_table())
+        else {
+            return Ok(ProjectMetadata::default());
+        };
+
+        // fields inherited with `{ workspace = true }` are not strings and are skipped
+        let string = |key: &str| {
+            package
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string())
+        };
+        let strings = |key: &str| -> Vec<String> {
+            package
+                .get(key)
+                .and_then(|value| value.as_array())
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|value| value.as_str())
+                        .map(|value| value.to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Ok(ProjectMetadata {
+            description: string("description"),
+            version: string("version"),
+            authors: strings("authors")
+                .iter()
+                .map(|author| author_name(author))
+                .collect(),
+            license: string("license"),
+            homepage: string("homepage"),
+            repository: string("repository"),
+            documentation: string("documentation"),
+            keywords: strings("keywords"),
+        })
+    }
+
+    fn get_package_names(&self, project_root: &Path) -> Result<Vec<String>, GetMetadataError> {
+        let cargo_toml_text = std::fs::read_to_string(project_root.join(PROJECT_FILE))
+            .map_err(|source| GetMetadataError::FailedToReadProjectManifest { source })?;
+        let cargo_toml: toml::Table = toml::from_str(&cargo_toml_text)
+            .map_err(|source| GetMetadataError::FailedToParseProjectTomlManifest { source })?;
+
+        Ok(cargo_toml
+            .get("package")
+            .and_then(|package| package.get("name"))
+            .and_then(|name| name.as_str())
+            .map(|name| vec![name.to_string()])
+            .unwrap_or_default())
+    }
+}
+
+impl ComponentType for Rust {
+    fn infer_component_type(&self, project_root: &Path) -> Option<ComponentTypeInference> {
+        let cargo_toml_text = std::fs::read_to_string(project_root.join(PROJECT_FILE)).ok()?;
+        let cargo_toml: toml::Table = toml::from_str(&cargo_toml_text).ok()?;
+
+        let dependencies: Vec<String> = cargo_toml
+            .get("dependencies")
+            .and_then(|dependencies| dependencies.as_table())
+            .map(|dependencies| dependencies.keys().cloned().collect())
+            .unwrap_or_default();
+
+        let has_binary = cargo_toml.contains_key("bin")
+            || project_root.join("src").join("main.rs").exists()
+            || project_root.join("src").join("bin").is_dir();
+
+        if !has_binary {
+            return Some(ComponentTypeInference::new("library", "rust-library"));
+        }
+
+        if depends_on_any(
+            &dependencies,
+            &[
+                "axum",
+                "actix-web",
+                "rocket",
+                "warp",
+                "tide",
+                "poem",
+                "hyper",
+                "tonic",
+            ],
+        ) {
+            return Some(ComponentTypeInference::new("service", "rust-http-service"));
+        }
+
+        if depends_on_any(&dependencies, &["clap", "structopt", "argh"]) {
+            return Some(ComponentTypeInference::new("cli", "rust-cli"));
+        }
+
+        Some(ComponentTypeInference::new("service", "rust-binary"))
+    }
 }
 
 impl crate::languages::Language for Rust {}
@@ -29,3 +337,162 @@ impl std::fmt::Display for Rust {
         write!(f, "Rust")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join(PROJECT_FILE),
+            r#"[package]
+name = "checkout"
+
+[dependencies]
+serde = "1"
+ledger = { git = "ssh://git@bitbucket.org/bxbdigital/ledger.git", tag = "v1.4.0" }
+tokio = { version = "1", features = ["full"] }
+api = { path = "../api" }
+cart_v2 = { package = "cart", version = "2.1", registry = "bxbdigital" }
+
+[target.'cfg(unix)'.dev-dependencies.fixtures]
+git = "https://bitbucket.org/bxbdigital/fixtures"
+branch = "main"
+"#,
+        )?;
+
+        let dependencies = Rust::default()
+            .get_internal_dependencies(test_dir.path(), &InternalConfig::default().compile()?)?;
+        let summary: Vec<(&str, Option<&str>, String, usize)> = dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.name.as_str(),
+                    dependency.version.as_deref(),
+                    dependency
+                        .source
+                        .as_ref()
+                        .map(|source| source.to_string())
+                        .unwrap_or_default(),
+                    dependency
+                        .location
+                        .as_ref()
+                        .map(|location| location.line)
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("api", None, "path:../api".to_string(), 8),
+                ("cart", Some("2.1"), "registry:bxbdigital".to_string(), 9),
+                (
+                    "ledger",
+                    Some("v1.4.0"),
+                    "git:ssh://git@bitbucket.org/bxbdigital/ledger.git#tag=v1.4.0".to_string(),
+                    6
+                ),
+                (
+                    "fixtures",
+                    None,
+                    "git:https://bitbucket.org/bxbdigital/fixtures#branch=main".to_string(),
+                    11
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_internal_dependencies_workspace() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+        std::fs::write(
+            test_dir.path().join(PROJECT_FILE),
+            r#"[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+ledger = { git = "https://bitbucket.org/bxbdigital/ledger", rev = "4f2a9c1" }
+cart = "2.1"
+"#,
+        )?;
+        let crate_root = test_dir.path().join("crates").join("checkout");
+        std::fs::create_dir_all(&crate_root)?;
+        std::fs::write(
+            crate_root.join(PROJECT_FILE),
+            r#"[package]
+name = "checkout"
+
+[dependencies]
+ledger = { workspace = true, features = ["sqlx"] }
+cart = { workspace = true }
+crypto = { version = "0.3", registry = "crates-mirror" }
+"#,
+        )?;
+
+        let dependencies = Rust::default()
+            .get_internal_dependencies(&crate_root, &InternalConfig::default().compile()?)?;
+        let summary: Vec<(&str, Option<&str>, String)> = dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.name.as_str(),
+                    dependency.version.as_deref(),
+                    dependency
+                        .source
+                        .as_ref()
+                        .map(|source| source.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        // only the workspace's git dependency is internal, the mirror isn't one of our registries
+        assert_eq!(
+            summary,
+            vec![(
+                "ledger",
+                Some("4f2a9c1"),
+                "git:https://bitbucket.org/bxbdigital/ledger#rev=4f2a9c1".to_string()
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_infer_component_type() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join("src"))?;
+        std::fs::write(
+            test_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"ledger\"\n\n[dependencies]\naxum = \"0.7\"\n",
+        )?;
+        std::fs::write(test_dir.path().join("src").join("lib.rs"), "")?;
+
+        let inference = Rust::default().infer_component_type(test_dir.path());
+        assert_eq!(
+            inference,
+            Some(ComponentTypeInference::new("library", "rust-library"))
+        );
+
+        std::fs::write(test_dir.path().join("src").join("main.rs"), "fn main() {}")?;
+
+        let inference = Rust::default().infer_component_type(test_dir.path());
+        assert_eq!(
+            inference,
+            Some(ComponentTypeInference::new("service", "rust-http-service"))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WriteCatalogFilesError {
+    #[error("Failed to serialise the Location entity: {source}")]
+    FailedToSerialiseLocation { source: serde_yml::Error },
+
+    #[error("Failed to write the catalog info file {path}: {source}")]
+    FailedToWriteCatalogFile {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to remove the stale catalog info file {path}: {source}")]
+    FailedToRemoveStaleCatalogFile {
+        path: String,
+        source: std::io::Error,
+    },
+}
@@ -0,0 +1,301 @@
+mod errors;
+
+use crate::apis::relative_location;
+use crate::backstage::{CATALOG_INFO_FILE, GENERATED_BY_ANNOTATION, Location, entity_name};
+use crate::config::{LayoutConfig, LayoutMode};
+use crate::git::EXCLUDED_DIRECTORIES;
+pub use errors::WriteCatalogFilesError;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The YAML documents generated for a project.
+#[derive(Debug, Clone)]
+pub struct ProjectDocuments {
+    pub project_root: PathBuf,
+    pub documents: Vec<String>,
+}
+
+/// The catalog info files written for a repository.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LayoutReport {
+    pub written: Vec<PathBuf>,
+
+    /// Hand-written catalog info files that were left as they are rather than overwritten
+    pub skipped: Vec<PathBuf>,
+
+    /// Catalog info files that we generated before but no longer generate. Hand-written
+    /// catalog info files are never stale
+    pub stale: Vec<PathBuf>,
+
+    /// Whether the stale files were removed
+    pub removed_stale: bool,
+}
+
+/// Works out the contents of each catalog info file. In `perProject` mode every project gets
+/// its own file and the root file lists them in a `Location`, while `single` mode puts every
+/// entity in the root file.
+pub fn plan_catalog_files(
+    repo_root: &Path,
+    repo_name: &str,
+    projects: &[ProjectDocuments],
+    config: &LayoutConfig,
+) -> Result<BTreeMap<PathBuf, Vec<String>>, WriteCatalogFilesError> {
+    let root_file = repo_root.join(CATALOG_INFO_FILE);
+    let mut files: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+
+    for project in projects {
+        let catalog_dir = config.catalog_dir(repo_root, &project.project_root);
+        files
+            .entry(catalog_dir.join(CATALOG_INFO_FILE))
+            .or_default()
+            .extend(project.documents.iter().cloned());
+    }
+
+    if config.mode == LayoutMode::PerProject {
+        let targets: Vec<String> = files
+            .keys()
+            .filter(|path| **path != root_file)
+            .map(|path| relative_location(repo_root, path))
+            .collect();
+
+        if !targets.is_empty() {
+            let location = Location::new(&entity_name(repo_name), targets);
+            let location_document = serde_yml::to_string(&location)
+                .map_err(|source| WriteCatalogFilesError::FailedToSerialiseLocation { source })?;
+            files
+                .entry(root_file)
+                .or_default()
+                .insert(0, location_document);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Writes the catalog info files for the projects, leaving hand-written ones alone, and finds
+/// the catalog info files left over from projects that have moved or gone, removing them if
+/// `remove_stale` is set.
+pub fn write_catalog_files(
+    repo_root: &Path,
+    repo_name: &str,
+    projects: &[ProjectDocuments],
+    config: &LayoutConfig,
+    remove_stale: bool,
+) -> Result<LayoutReport, WriteCatalogFilesError> {
+    let files = plan_catalog_files(repo_root, repo_name, projects, config)?;
+
+    let mut report = LayoutReport::default();
+    for (path, documents) in &files {
+        if path.exists() && !is_generated(path) {
+            report.skipped.push(path.clone());
+            continue;
+        }
+
+        let text: String = documents
+            .iter()
+            .map(|document| format!("---\n{document}"))
+            .collect();
+        std::fs::write(path, text).map_err(|source| {
+            WriteCatalogFilesError::FailedToWriteCatalogFile {
+                path: format!("{}", path.display()),
+                source,
+            }
+        })?;
+        report.written.push(path.clone());
+    }
+
+    report.stale = find_catalog_files(repo_root)
+        .into_iter()
+        .filter(|path| !files.contains_key(path) && is_generated(path))
+        .collect();
+
+    if remove_stale {
+        for path in &report.stale {
+            std::fs::remove_file(path).map_err(|source| {
+                WriteCatalogFilesError::FailedToRemoveStaleCatalogFile {
+                    path: format!("{}", path.display()),
+                    source,
+                }
+            })?;
+        }
+        report.removed_stale = true;
+    }
+
+    Ok(report)
+}
+
+/// Whether the catalog info file at `path` holds an entity we generated, going by the
+/// [`GENERATED_BY_ANNOTATION`]. Files that can't be read or parsed are left alone.
+fn is_generated(path: &Path) -> bool {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return false;
+    };
+
+    serde_yml::Deserializer::from_str(&text).any(|document| {
+        serde_yml::Value::deserialize(document).is_ok_and(|entity| {
+            entity
+                .get("metadata")
+                .and_then(|metadata| metadata.get("annotations"))
+                .and_then(|annotations| annotations.get(GENERATED_BY_ANNOTATION))
+                .is_some()
+        })
+    })
+}
+
+/// Finds every catalog info file in the repository, skipping hidden directories and those
+/// holding dependencies or build output.
+fn find_catalog_files(repo_root: &Path) -> Vec<PathBuf> {
+    let mut catalog_files = vec![];
+    let mut directories = vec![repo_root.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                if !file_name.starts_with('.')
+                    && !EXCLUDED_DIRECTORIES.contains(&file_name.as_str())
+                {
+                    directories.push(path);
+                }
+            } else if file_name == CATALOG_INFO_FILE {
+                catalog_files.push(path);
+            }
+        }
+    }
+
+    catalog_files.sort();
+    catalog_files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    /// A generated entity of `kind`.
+    fn generated(kind: &str) -> String {
+        format!(
+            "kind: {kind}\nmetadata:\n  annotations:\n    {GENERATED_BY_ANNOTATION}: metaforge\n"
+        )
+    }
+
+    #[test]
+    fn test_write_catalog_files() -> Result<()> {
+        let test_dir = tempdir()?;
+        for project in ["api", "worker", "old", "manual", "target/package"] {
+            std::fs::create_dir_all(test_dir.path().join(project))?;
+        }
+        std::fs::write(
+            test_dir.path().join("old").join(CATALOG_INFO_FILE),
+            generated("Component"),
+        )?;
+        // hand-written files and build output are never stale
+        let manual_path = test_dir.path().join("manual").join(CATALOG_INFO_FILE);
+        std::fs::write(&manual_path, "kind: Resource\nmetadata:\n  name: queue\n")?;
+        let build_output_path = test_dir
+            .path()
+            .join("target/package")
+            .join(CATALOG_INFO_FILE);
+        std::fs::write(&build_output_path, generated("Component"))?;
+
+        let projects = vec![
+            ProjectDocuments {
+                project_root: test_dir.path().join("api"),
+                documents: vec![generated("Component"), generated("API")],
+            },
+            ProjectDocuments {
+                project_root: test_dir.path().join("worker"),
+                documents: vec![generated("Component")],
+            },
+        ];
+
+        let report = write_catalog_files(
+            test_dir.path(),
+            "platform",
+            &projects,
+            &LayoutConfig::default(),
+            false,
+        )?;
+
+        assert_eq!(
+            report.stale,
+            vec![test_dir.path().join("old").join(CATALOG_INFO_FILE)]
+        );
+        assert_eq!(
+            std::fs::read_to_string(test_dir.path().join("api").join(CATALOG_INFO_FILE))?,
+            format!("---\n{}---\n{}", generated("Component"), generated("API"))
+        );
+        let root_text = std::fs::read_to_string(test_dir.path().join(CATALOG_INFO_FILE))?;
+        let location: Location = serde_yml::from_str(root_text.trim_start_matches("---\n"))?;
+        assert_eq!(
+            location.spec.targets,
+            vec!["./api/catalog-info.yaml", "./worker/catalog-info.yaml"]
+        );
+
+        // the stale files are removed and the single file mode puts everything in the root file
+        let config = LayoutConfig {
+            mode: LayoutMode::Single,
+            ..LayoutConfig::default()
+        };
+        let report = write_catalog_files(test_dir.path(), "platform", &projects, &config, true)?;
+
+        assert_eq!(
+            report.written,
+            vec![test_dir.path().join(CATALOG_INFO_FILE)]
+        );
+        assert_eq!(report.stale.len(), 3);
+        assert!(!test_dir.path().join("old").join(CATALOG_INFO_FILE).exists());
+        assert!(!test_dir.path().join("api").join(CATALOG_INFO_FILE).exists());
+        assert!(manual_path.exists());
+        assert!(build_output_path.exists());
+        assert_eq!(
+            std::fs::read_to_string(test_dir.path().join(CATALOG_INFO_FILE))?,
+            format!(
+                "---\n{}---\n{}---\n{}",
+                generated("Component"),
+                generated("API"),
+                generated("Component")
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_catalog_files_keeps_hand_written() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join("api"))?;
+        let root_path = test_dir.path().join(CATALOG_INFO_FILE);
+        let root_text = "kind: Location\nmetadata:\n  name: handmade\nspec:\n  targets: []\n";
+        std::fs::write(&root_path, root_text)?;
+
+        let projects = vec![ProjectDocuments {
+            project_root: test_dir.path().join("api"),
+            documents: vec![generated("Component")],
+        }];
+        let report = write_catalog_files(
+            test_dir.path(),
+            "platform",
+            &projects,
+            &LayoutConfig::default(),
+            true,
+        )?;
+
+        assert_eq!(report.skipped, vec![root_path.clone()]);
+        assert_eq!(
+            report.written,
+            vec![test_dir.path().join("api").join(CATALOG_INFO_FILE)]
+        );
+        assert_eq!(std::fs::read_to_string(&root_path)?, root_text);
+
+        Ok(())
+    }
+}
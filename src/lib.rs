@@ -1,8 +1,24 @@
+pub mod apis;
 pub mod backstage;
+pub mod check;
 pub mod cli;
+pub mod codeowners;
+pub mod config;
+pub mod dependents;
+pub mod docker;
 pub mod git;
+pub mod graph;
+pub mod index;
 pub mod languages;
+pub mod layout;
+pub mod lockfile;
+pub mod ownership;
 pub mod project;
+pub mod readme;
+pub mod sbom;
+pub mod scan;
+pub mod skew;
+pub mod techdocs;
 // use crate::project::Project;
 
 // pub async fn scan_repo<'a>() -> Result<Vec<Project<'a>>, ScanRepoError> {
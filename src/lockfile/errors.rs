@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReadLockfileError {
+    #[error("Failed to read the lockfile {path}: {source}")]
+    FailedToReadLockfile {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the TOML lockfile: {source}")]
+    FailedToParseTomlLockfile { source: toml::de::Error },
+
+    #[error("Failed to parse the JSON lockfile: {source}")]
+    FailedToParseJsonLockfile { source: serde_json::Error },
+
+    #[error("Failed to parse the YAML lockfile: {source}")]
+    FailedToParseYamlLockfile { source: serde_yml::Error },
+}
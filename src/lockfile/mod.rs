@@ -0,0 +1,839 @@
+mod errors;
+
+use crate::backstage::Component;
+use crate::config::InternalPatterns;
+use crate::index::DependencyIndex;
+pub use errors::ReadLockfileError;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// A package pinned by a lockfile.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Option<String>,
+
+    /// The commit built, for packages fetched from git
+    pub commit: Option<String>,
+
+    /// Where the package was fetched from, e.g. a tarball or git URL
+    pub source: Option<String>,
+
+    /// The names of the packages this package depends on, when the lockfile records them
+    pub dependencies: Vec<String>,
+}
+
+/// The packages pinned by a `Cargo.lock`, `go.sum`, `package-lock.json`, `yarn.lock`,
+/// `pnpm-lock.yaml` or `poetry.lock` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lockfile {
+    pub path: PathBuf,
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct CargoLock {
+    package: Vec<CargoLockPackage>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct CargoLockPackage {
+    name: String,
+    version: Option<String>,
+
+    /// e.g. `git+https://bitbucket.org/bxbdigital/ledger?tag=v1.4.0#<commit>`
+    source: Option<String>,
+
+    /// e.g. `serde` or `ledger 1.4.0 (git+https://...)`
+    dependencies: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct PackageLock {
+    /// The packages of lockfile versions 2 and 3, keyed by their `node_modules` path
+    packages: BTreeMap<String, PackageLockEntry>,
+
+    /// The packages of lockfile version 1, keyed by their name
+    dependencies: BTreeMap<String, PackageLockEntry>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct PackageLockEntry {
+    name: Option<String>,
+    version: Option<String>,
+    resolved: Option<String>,
+
+    /// Version ranges in lockfile versions 2 and 3, nested entries in version 1
+    dependencies: BTreeMap<String, serde_json::Value>,
+
+    /// The version ranges of lockfile version 1
+    requires: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct PoetryLock {
+    package: Vec<PoetryLockPackage>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct PoetryLockPackage {
+    name: String,
+    version: Option<String>,
+    source: Option<PoetryLockSource>,
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct PoetryLockSource {
+    url: Option<String>,
+    resolved_reference: Option<String>,
+}
+
+impl Lockfile {
+    /// Finds the closest of the lockfiles named `file_names` in the project root or one of its
+    /// parents, e.g. the root of a Cargo or npm workspace, stopping at the repository root.
+    pub fn find(
+        project_root: &Path,
+        file_names: &[&str],
+    ) -> Result<Option<Lockfile>, ReadLockfileError> {
+        for directory in project_root.ancestors() {
+            for file_name in file_names {
+                let path = directory.join(file_name);
+                if path.is_file() {
+                    return Lockfile::read(&path).map(Some);
+                }
+            }
+            if directory.join(".git").exists() {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads a lockfile, recognising its format from its file name.
+    pub fn read(path: &Path) -> Result<Lockfile, ReadLockfileError> {
+        let text = std::fs::read_to_string(path).map_err(|source| {
+            ReadLockfileError::FailedToReadLockfile {
+                path: format!("{}", path.display()),
+                source,
+            }
+        })?;
+        // editors on Windows sometimes save lockfiles with a byte order mark
+        let text = text.trim_start_matches('\u{feff}');
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let packages = match file_name.as_str() {
+            "Cargo.lock" => parse_cargo_lock(text)?,
+            "go.sum" => parse_go_sum(text),
+            "package-lock.json" => parse_package_lock(text)?,
+            "yarn.lock" => parse_yarn_lock(text),
+            "pnpm-lock.yaml" => parse_pnpm_lock(text)?,
+            "poetry.lock" => parse_poetry_lock(text)?,
+            _ => vec![],
+        };
+
+        Ok(Lockfile {
+            path: path.to_path_buf(),
+            packages,
+        })
+    }
+
+    /// The package named `name`, ignoring case, `-`/`_` differences and, for unscoped names
+    /// like `design-system`, an internal scope or repository path like
+    /// `@bxbdigital/design-system`.
+    pub fn get(&self, name: &str, internal: &InternalPatterns) -> Option<&LockedPackage> {
+        let name = normalise(name);
+        self.packages
+            .iter()
+            .find(|package| normalise(&package.name) == name)
+            .or_else(|| {
+                self.packages
+                    .iter()
+                    .find(|package| is_internal_alias(&package.name, &name, internal))
+            })
+    }
+
+    /// The package named `name` at exactly `version`.
+    fn get_version(&self, name: &str, version: &str) -> Option<&LockedPackage> {
+        self.packages
+            .iter()
+            .find(|package| package.name == name && package.version.as_deref() == Some(version))
+    }
+
+    /// Whether the lockfile is a `go.sum`, which holds the checksums of every module version
+    /// considered rather than the versions selected. The version a module is built at comes
+    /// from the `require` directives of the `go.mod` instead.
+    fn is_checksum_database(&self) -> bool {
+        self.path
+            .file_name()
+            .is_some_and(|file_name| file_name == "go.sum")
+    }
+
+    /// The packages depended on by the packages named `roots`, directly or not. When the
+    /// lockfile doesn't record which packages depend on which, or none of the roots are in it,
+    /// every package is returned.
+    pub fn dependencies_of(
+        &self,
+        roots: &[String],
+        internal: &InternalPatterns,
+    ) -> Vec<&LockedPackage> {
+        let roots: Vec<&LockedPackage> = roots
+            .iter()
+            .filter_map(|root| self.get(root, internal))
+            .collect();
+        let has_graph = self
+            .packages
+            .iter()
+            .any(|package| !package.dependencies.is_empty());
+        if roots.is_empty() || !has_graph {
+            return self.packages.iter().collect();
+        }
+
+        let mut visited: BTreeSet<String> =
+            roots.iter().map(|root| normalise(&root.name)).collect();
+        let mut queue: VecDeque<&LockedPackage> = roots.into_iter().collect();
+        let mut dependencies = vec![];
+        while let Some(package) = queue.pop_front() {
+            for name in &package.dependencies {
+                if !visited.insert(normalise(name)) {
+                    continue;
+                }
+                if let Some(dependency) = self.get(name, internal) {
+                    dependencies.push(dependency);
+                    queue.push_back(dependency);
+                }
+            }
+        }
+        dependencies
+    }
+
+    /// Records the resolved version and commit of each of the project's `components`, and adds
+    /// the internal packages the project only depends on through other packages as indirect
+    /// components. `roots` are the names the project is published under.
+    pub fn lock(
+        &self,
+        components: Vec<Component>,
+        roots: &[String],
+        internal: &InternalPatterns,
+        index: &DependencyIndex,
+    ) -> Vec<Component> {
+        self.lock_matching(components, roots, internal, |package| {
+            index.get(&package.name).is_some()
+                || internal.is_internal_scope(&package.name)
+                || internal.is_internal_repo(&package.name)
+                || package
+                    .source
+                    .as_ref()
+                    .is_some_and(|source| internal.is_internal_repo(source))
+        })
+    }
+
+    /// Like [`Lockfile::lock`], but adds every package the project depends on through other
+    /// packages, internal or not.
+    pub fn lock_all(
+        &self,
+        components: Vec<Component>,
+        roots: &[String],
+        internal: &InternalPatterns,
+    ) -> Vec<Component> {
+        self.lock_matching(components, roots, internal, |_| true)
+    }
+
+    fn lock_matching(
+        &self,
+        components: Vec<Component>,
+        roots: &[String],
+        internal: &InternalPatterns,
+        is_wanted: impl Fn(&LockedPackage) -> bool,
+    ) -> Vec<Component> {
+        let mut locked: Vec<Component> = components
+            .into_iter()
+            .map(|mut component| {
+                let package = if self.is_checksum_database() {
+                    component
+                        .version
+                        .as_deref()
+                        .and_then(|version| self.get_version(&component.name, version))
+                } else {
+                    self.get(&component.name, internal)
+                };
+                if let Some(package) = package {
+                    component.resolved_version = package.version.clone();
+                    component.commit = package.commit.clone();
+                }
+                component
+            })
+            .collect();
+
+        // a go.sum doesn't say which modules are selected, but the go.mod lists them all
+        if self.is_checksum_database() {
+            return locked;
+        }
+
+        for package in self.dependencies_of(roots, internal) {
+            let is_known = locked.iter().any(|component| {
+                let name = normalise(&component.name);
+                normalise(&package.name) == name
+                    || is_internal_alias(&package.name, &name, internal)
+            });
+            if is_known || roots.contains(&package.name) || !is_wanted(package) {
+                continue;
+            }
+
+            let mut component = Component::at(&package.name, None).as_indirect();
+            component.resolved_version = package.version.clone();
+            component.commit = package.commit.clone();
+            locked.push(component);
+        }
+
+        locked
+    }
+}
+
+/// Normalises a package name for comparison, e.g. `Ledger_Client` to `ledger-client`.
+fn normalise(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Whether `package_name` is the normalised, unscoped `name` under an internal scope or
+/// repository path, e.g. `@bxbdigital/ui` or `bitbucket.org/bxbdigital/ui` for `ui`.
+fn is_internal_alias(package_name: &str, name: &str, internal: &InternalPatterns) -> bool {
+    let scoped_name = internal
+        .is_internal_scope(package_name)
+        .then(|| package_name.split_once('/').map(|(_, rest)| rest))
+        .flatten();
+    scoped_name
+        .or_else(|| internal.repo_name(package_name))
+        .is_some_and(|unscoped| normalise(unscoped) == name)
+}
+
+/// The commit in the fragment of a git URL, e.g. `git+ssh://host/repo.git#<commit>` or
+/// `repo@git+ssh://host/repo.git#commit=<commit>`.
+fn url_commit(url: &str) -> Option<String> {
+    let (_, fragment) = url.rsplit_once('#')?;
+    let commit = fragment.trim_start_matches("commit=");
+    is_commit(commit).then(|| commit.to_string())
+}
+
+fn is_commit(text: &str) -> bool {
+    (7..=40).contains(&text.len()) && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The commit abbreviated in a Go pseudo-version, e.g. `v0.0.0-20240101120000-abcdef123456`.
+fn pseudo_version_commit(version: &str) -> Option<String> {
+    let version = version.trim_end_matches("+incompatible");
+    let (rest, commit) = version.rsplit_once('-')?;
+    let timestamp = rest.rsplit(['-', '.']).next()?;
+    (timestamp.len() == 14 && timestamp.chars().all(|c| c.is_ascii_digit()) && is_commit(commit))
+        .then(|| commit.to_string())
+}
+
+/// Splits a package spec like `@bxbdigital/ui@^1.2.0` or `ui@npm:1.2.0` into its name and the
+/// rest, ignoring the `@` a scope starts with.
+fn split_package_spec(spec: &str) -> (&str, &str) {
+    let scope_length = spec.len() - spec.strip_prefix('@').unwrap_or(spec).len();
+    match spec[scope_length..].find('@') {
+        Some(offset) => (
+            &spec[..scope_length + offset],
+            &spec[scope_length + offset + 1..],
+        ),
+        None => (spec, ""),
+    }
+}
+
+fn parse_cargo_lock(text: &str) -> Result<Vec<LockedPackage>, ReadLockfileError> {
+    let cargo_lock: CargoLock = toml::from_str(text)
+        .map_err(|source| ReadLockfileError::FailedToParseTomlLockfile { source })?;
+
+    Ok(cargo_lock
+        .package
+        .into_iter()
+        .map(|package| LockedPackage {
+            commit: package.source.as_deref().and_then(url_commit),
+            dependencies: package
+                .dependencies
+                .iter()
+                .filter_map(|dependency| dependency.split_whitespace().next())
+                .map(|name| name.to_string())
+                .collect(),
+            name: package.name,
+            version: package.version,
+            source: package.source,
+        })
+        .collect())
+}
+
+/// Reads the module versions in a `go.sum`, which doesn't record which module depends on which.
+/// Lines for a module's `go.mod` only are skipped, as the module isn't built. Every version the
+/// module graph considered is listed, not only the one selected.
+fn parse_go_sum(text: &str) -> Vec<LockedPackage> {
+    let mut packages: Vec<LockedPackage> = vec![];
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(module), Some(version)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if version.ends_with("/go.mod") {
+            continue;
+        }
+
+        packages.push(LockedPackage {
+            name: module.to_string(),
+            version: Some(version.to_string()),
+            commit: pseudo_version_commit(version),
+            source: None,
+            dependencies: vec![],
+        });
+    }
+    packages
+}
+
+fn parse_package_lock(text: &str) -> Result<Vec<LockedPackage>, ReadLockfileError> {
+    let package_lock: PackageLock = serde_json::from_str(text)
+        .map_err(|source| ReadLockfileError::FailedToParseJsonLockfile { source })?;
+
+    let locked_package = |name: &str, entry: &PackageLockEntry| {
+        // version 1 records git dependencies' URLs as their version
+        let version = entry
+            .version
+            .clone()
+            .filter(|version| !version.contains(':'));
+        let source = entry.resolved.clone().or_else(|| {
+            entry
+                .version
+                .clone()
+                .filter(|version| version.contains(':'))
+        });
+        LockedPackage {
+            name: name.to_string(),
+            version,
+            commit: source.as_deref().and_then(url_commit),
+            source,
+            dependencies: entry
+                .dependencies
+                .keys()
+                .chain(entry.requires.keys())
+                .cloned()
+                .collect(),
+        }
+    };
+
+    if package_lock.packages.is_empty() {
+        return Ok(package_lock
+            .dependencies
+            .iter()
+            .map(|(name, entry)| locked_package(name, entry))
+            .collect());
+    }
+
+    Ok(package_lock
+        .packages
+        .iter()
+        .filter_map(|(key, entry)| {
+            let name = match key.rsplit_once("node_modules/") {
+                Some((_, name)) => name.to_string(),
+                // the root package and workspace packages are keyed by their directory
+                None => entry.name.clone()?,
+            };
+            Some(locked_package(&name, entry))
+        })
+        .collect())
+}
+
+/// Reads both the classic `yarn.lock` format and the YAML based format of Yarn 2 and later.
+fn parse_yarn_lock(text: &str) -> Vec<LockedPackage> {
+    let unquote = |text: &str| text.trim().trim_matches('"').to_string();
+
+    let mut packages: Vec<LockedPackage> = vec![];
+    let mut in_dependencies = false;
+    for line in text.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            // e.g. `"@bxbdigital/ui@^1.0.0", "@bxbdigital/ui@^1.1.0":`
+            let spec = unquote(line.trim_end_matches(':').split(", ").next().unwrap_or(""));
+            let (name, _) = split_package_spec(&spec);
+            packages.push(LockedPackage {
+                name: name.to_string(),
+                ..LockedPackage::default()
+            });
+            in_dependencies = false;
+            continue;
+        }
+
+        let Some(package) = packages.last_mut() else {
+            continue;
+        };
+        let line = line.trim();
+        let (key, value) = match line.split_once([' ', ':']) {
+            Some((key, value)) => (unquote(key), unquote(value.trim_start_matches(':'))),
+            None => (unquote(line), String::new()),
+        };
+
+        if indent > 2 {
+            if in_dependencies {
+                package.dependencies.push(key);
+            }
+            continue;
+        }
+
+        in_dependencies = key == "dependencies";
+        match key.as_str() {
+            "version" => package.version = Some(value),
+            "resolved" | "resolution" => {
+                package.commit = url_commit(&value);
+                package.source = Some(value);
+            }
+            _ => {}
+        }
+    }
+
+    packages.retain(|package| !package.name.starts_with("__"));
+    packages
+}
+
+fn parse_pnpm_lock(text: &str) -> Result<Vec<LockedPackage>, ReadLockfileError> {
+    let pnpm_lock: serde_yml::Value = serde_yml::from_str(text)
+        .map_err(|source| ReadLockfileError::FailedToParseYamlLockfile { source })?;
+
+    let mut packages = vec![];
+    let Some(entries) = pnpm_lock
+        .get("packages")
+        .and_then(|entries| entries.as_mapping())
+    else {
+        return Ok(packages);
+    };
+    for (key, entry) in entries {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+
+        // e.g. `/@bxbdigital/ui@1.2.0(react@18.2.0)`, or `/@bxbdigital/ui/1.2.0` before v6
+        let key = key.trim_start_matches('/');
+        let key = key.split('(').next().unwrap_or(key);
+        let (name, version) = match split_package_spec(key) {
+            (name, "") => key.rsplit_once('/').unwrap_or((name, "")),
+            spec => spec,
+        };
+
+        let resolution = entry.get("resolution");
+        let string = |value: Option<&serde_yml::Value>| {
+            value
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string())
+        };
+        // from v9 the dependencies of packages are recorded in `snapshots`
+        let dependencies = entry
+            .get("dependencies")
+            .or_else(|| {
+                pnpm_lock
+                    .get("snapshots")
+                    .and_then(|snapshots| snapshots.get(key))
+                    .and_then(|snapshot| snapshot.get("dependencies"))
+            })
+            .and_then(|dependencies| dependencies.as_mapping())
+            .map(|dependencies| {
+                dependencies
+                    .keys()
+                    .filter_map(|name| name.as_str().map(|name| name.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        packages.push(LockedPackage {
+            name: name.to_string(),
+            version: (!version.is_empty()).then(|| version.to_string()),
+            commit: string(resolution.and_then(|resolution| resolution.get("commit"))),
+            source: string(resolution.and_then(|resolution| {
+                resolution.get("repo").or_else(|| resolution.get("tarball"))
+            })),
+            dependencies,
+        });
+    }
+
+    Ok(packages)
+}
+
+fn parse_poetry_lock(text: &str) -> Result<Vec<LockedPackage>, ReadLockfileError> {
+    let poetry_lock: PoetryLock = toml::from_str(text)
+        .map_err(|source| ReadLockfileError::FailedToParseTomlLockfile { source })?;
+
+    Ok(poetry_lock
+        .package
+        .into_iter()
+        .map(|package| LockedPackage {
+            commit: package
+                .source
+                .as_ref()
+                .and_then(|source| source.resolved_reference.clone())
+                .filter(|reference| is_commit(reference)),
+            source: package.source.and_then(|source| source.url),
+            dependencies: package.dependencies.into_keys().collect(),
+            name: package.name,
+            version: package.version,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InternalConfig;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_cargo_lock() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+        std::fs::create_dir(test_dir.path().join("checkout"))?;
+        std::fs::write(
+            test_dir.path().join("Cargo.lock"),
+            r#"version = 3
+
+[[package]]
+name = "checkout"
+version = "0.1.0"
+dependencies = ["ledger", "serde"]
+
+[[package]]
+name = "ledger"
+version = "1.4.0"
+source = "git+ssh://git@bitbucket.org/bxbdigital/ledger.git?tag=v1.4.0#0123456789abcdef0123456789abcdef01234567"
+dependencies = ["money 0.3.1 (git+ssh://git@bitbucket.org/bxbdigital/money.git#89abcdef)"]
+
+[[package]]
+name = "money"
+version = "0.3.1"
+source = "git+ssh://git@bitbucket.org/bxbdigital/money.git#89abcdef"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "unrelated"
+version = "0.2.0"
+source = "git+ssh://git@bitbucket.org/bxbdigital/unrelated.git#fedcba98"
+"#,
+        )?;
+
+        let lockfile = Lockfile::find(&test_dir.path().join("checkout"), &["Cargo.lock"])?
+            .expect("the workspace lockfile to be found");
+
+        let components = lockfile.lock(
+            vec![Component::at("ledger", None).with_version("1.4")],
+            &["checkout".to_string()],
+            &InternalConfig::default().compile()?,
+            &DependencyIndex::default(),
+        );
+        let summary: Vec<(&str, Option<&str>, Option<&str>, bool)> = components
+            .iter()
+            .map(|component| {
+                (
+                    component.name.as_str(),
+                    component.resolved_version.as_deref(),
+                    component.commit.as_deref(),
+                    component.indirect,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "ledger",
+                    Some("1.4.0"),
+                    Some("0123456789abcdef0123456789abcdef01234567"),
+                    false
+                ),
+                ("money", Some("0.3.1"), Some("89abcdef"), true),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_foreign_scope() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("package-lock.json"),
+            r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "storefront", "dependencies": { "ui": "^1.0.0", "@someone-else/forms": "^2.0.0" } },
+    "node_modules/@someone-else/ui": { "version": "9.9.9" },
+    "node_modules/@bxbdigital/ui": { "version": "1.2.0" },
+    "node_modules/@someone-else/forms": { "version": "2.0.0" }
+  }
+}"#,
+        )?;
+        let lockfile = Lockfile::read(&test_dir.path().join("package-lock.json"))?;
+        let internal = InternalConfig::default().compile()?;
+
+        assert_eq!(
+            lockfile
+                .get("ui", &internal)
+                .map(|package| package.name.as_str()),
+            Some("@bxbdigital/ui")
+        );
+        assert_eq!(lockfile.get("forms", &internal), None);
+
+        let components = lockfile.lock(
+            vec![Component::at("forms", None)],
+            &["storefront".to_string()],
+            &internal,
+            &DependencyIndex::default(),
+        );
+        let summary: Vec<(&str, Option<&str>)> = components
+            .iter()
+            .map(|component| {
+                (
+                    component.name.as_str(),
+                    component.resolved_version.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![("forms", None), ("@bxbdigital/ui", Some("1.2.0"))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_go_sum() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("go.sum"),
+            "bitbucket.org/bxbdigital/ledger v0.0.0-20240101120000-abcdef123456 h1:abc=\n\
+             bitbucket.org/bxbdigital/ledger v0.0.0-20240101120000-abcdef123456/go.mod h1:def=\n\
+             bitbucket.org/bxbdigital/ledger v0.0.0-20240301120000-0123456789ab h1:ghi=\n\
+             bitbucket.org/bxbdigital/ledger v0.0.0-20240301120000-0123456789ab/go.mod h1:jkl=\n\
+             bitbucket.org/bxbdigital/money v1.1.0 h1:mno=\n\
+             bitbucket.org/bxbdigital/unused v1.0.0 h1:pqr=\n",
+        )?;
+        let lockfile = Lockfile::read(&test_dir.path().join("go.sum"))?;
+
+        let components = lockfile.lock(
+            vec![
+                Component::at("bitbucket.org/bxbdigital/ledger", None)
+                    .with_version("v0.0.0-20240101120000-abcdef123456"),
+                Component::at("bitbucket.org/bxbdigital/money", None)
+                    .with_version("v1.2.0")
+                    .as_indirect(),
+            ],
+            &[],
+            &InternalConfig::default().compile()?,
+            &DependencyIndex::default(),
+        );
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> = components
+            .iter()
+            .map(|component| {
+                (
+                    component.name.as_str(),
+                    component.resolved_version.as_deref(),
+                    component.commit.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "bitbucket.org/bxbdigital/ledger",
+                    Some("v0.0.0-20240101120000-abcdef123456"),
+                    Some("abcdef123456")
+                ),
+                ("bitbucket.org/bxbdigital/money", None, None),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_lockfiles() {
+        let go_sum = parse_go_sum(
+            "bitbucket.org/bxbdigital/ledger v0.0.0-20240101120000-abcdef123456 h1:abc=\n\
+             bitbucket.org/bxbdigital/ledger v0.0.0-20240101120000-abcdef123456/go.mod h1:def=\n",
+        );
+        assert_eq!(go_sum[0].commit.as_deref(), Some("abcdef123456"));
+
+        let yarn_lock = parse_yarn_lock(
+            r#"# yarn lockfile v1
+
+"@bxbdigital/ui@^1.0.0", "@bxbdigital/ui@^1.1.0":
+  version "1.2.0"
+  resolved "git+ssh://git@bitbucket.org/bxbdigital/ui.git#abcdef1234567"
+  dependencies:
+    react "^18.0.0"
+"#,
+        );
+        assert_eq!(
+            yarn_lock,
+            vec![LockedPackage {
+                name: "@bxbdigital/ui".to_string(),
+                version: Some("1.2.0".to_string()),
+                commit: Some("abcdef1234567".to_string()),
+                source: Some(
+                    "git+ssh://git@bitbucket.org/bxbdigital/ui.git#abcdef1234567".to_string()
+                ),
+                dependencies: vec!["react".to_string()],
+            }]
+        );
+
+        let berry_lock = parse_yarn_lock(
+            r#"__metadata:
+  version: 6
+
+"@bxbdigital/ui@npm:^1.0.0":
+  version: 1.2.0
+  resolution: "@bxbdigital/ui@npm:1.2.0"
+"#,
+        );
+        assert_eq!(berry_lock.len(), 1);
+        assert_eq!(berry_lock[0].name, "@bxbdigital/ui");
+        assert_eq!(berry_lock[0].version.as_deref(), Some("1.2.0"));
+
+        assert_eq!(split_package_spec("ü@1.0.0"), ("ü", "1.0.0"));
+        assert_eq!(split_package_spec("@bxbdigital/ui"), ("@bxbdigital/ui", ""));
+    }
+
+    #[test]
+    fn test_read_yarn_lock_byte_order_mark() -> Result<()> {
+        let test_dir = tempdir()?;
+        let yarn_lock_path = test_dir.path().join("yarn.lock");
+        std::fs::write(
+            &yarn_lock_path,
+            "\u{feff}\"@bxbdigital/ui@^1.0.0\":\n  version \"1.2.0\"\n",
+        )?;
+
+        let lockfile = Lockfile::read(&yarn_lock_path)?;
+        assert_eq!(lockfile.packages.len(), 1);
+        assert_eq!(lockfile.packages[0].name, "@bxbdigital/ui");
+        assert_eq!(lockfile.packages[0].version.as_deref(), Some("1.2.0"));
+
+        Ok(())
+    }
+}
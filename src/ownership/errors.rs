@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SuggestOwnerError {
+    #[error("Failed to compile bot pattern regex: {source}")]
+    FailedToCompileBotRegex { source: regex::Error },
+
+    #[error("Failed to run git log: {source}")]
+    FailedToRunGitLog { source: std::io::Error },
+
+    #[error("Failed to read the owner mapping file {path}: {source}")]
+    FailedToReadMappingFile {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the owner mapping file {path}: {source}")]
+    FailedToParseMappingFile {
+        path: String,
+        source: serde_yml::Error,
+    },
+}
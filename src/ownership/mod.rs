@@ -0,0 +1,234 @@
+mod errors;
+
+use crate::backstage::entity_name;
+use crate::config::OwnersConfig;
+pub use errors::SuggestOwnerError;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// A commit to a project, as reported by `git log`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub author_name: String,
+    pub author_email: String,
+
+    /// The commit time in seconds since the Unix epoch
+    pub timestamp: i64,
+}
+
+/// An owner proposed from the commit history of a project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerSuggestion {
+    /// The entity ref of the suggested owner
+    pub owner: String,
+
+    /// The share of the project's weighted commits made by the owner, from 0 to 1
+    pub confidence: f64,
+
+    /// The number of commits considered, after excluding bots
+    pub commits: usize,
+}
+
+/// Suggests an owner for the project at `relative_dir` from the authors of its commits. Recent
+/// commits count for more, with a commit's weight halving every `halfLifeDays` before the
+/// newest commit. Returns `None` when there is no usable history.
+pub fn suggest_owner(
+    repo_root: &Path,
+    relative_dir: &Path,
+    config: &OwnersConfig,
+) -> Result<Option<OwnerSuggestion>, SuggestOwnerError> {
+    let commits = read_commits(repo_root, relative_dir)?;
+    let mappings = read_mapping_file(&repo_root.join(&config.mapping_file))?;
+
+    suggest_owner_from_commits(&commits, &mappings, config)
+}
+
+/// Weighs the commits by author, mapping each author to an owner through `mappings` so commits
+/// from members of the same group add up.
+pub fn suggest_owner_from_commits(
+    commits: &[Commit],
+    mappings: &BTreeMap<String, String>,
+    config: &OwnersConfig,
+) -> Result<Option<OwnerSuggestion>, SuggestOwnerError> {
+    let bot_regexes = config
+        .bots
+        .iter()
+        .map(|pattern| Regex::new(pattern))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| SuggestOwnerError::FailedToCompileBotRegex { source })?;
+
+    let commits: Vec<&Commit> = commits
+        .iter()
+        .filter(|commit| {
+            !bot_regexes.iter().any(|regex| {
+                regex.is_match(&commit.author_name) || regex.is_match(&commit.author_email)
+            })
+        })
+        .collect();
+    let Some(newest) = commits.iter().map(|commit| commit.timestamp).max() else {
+        return Ok(None);
+    };
+
+    let half_life = f64::from(config.half_life_days.max(1)) * SECONDS_PER_DAY;
+    let mut weights: BTreeMap<String, f64> = BTreeMap::new();
+    for commit in &commits {
+        let age = (newest - commit.timestamp).max(0) as f64;
+        let owner = mappings
+            .get(&commit.author_email.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| {
+                let user = commit.author_email.split('@').next().unwrap_or_default();
+                format!("user:default/{}", entity_name(user))
+            });
+        *weights.entry(owner).or_default() += 0.5_f64.powf(age / half_life);
+    }
+
+    let total: f64 = weights.values().sum();
+    // ties go to the first owner alphabetically so the suggestion is stable
+    let suggestion = weights
+        .into_iter()
+        .fold(None::<(String, f64)>, |best, (owner, weight)| match best {
+            Some((_, best_weight)) if best_weight >= weight => best,
+            _ => Some((owner, weight)),
+        })
+        .map(|(owner, weight)| OwnerSuggestion {
+            owner,
+            confidence: weight / total,
+            commits: commits.len(),
+        });
+
+    Ok(suggestion)
+}
+
+/// Reads the non-merge commits touching the project. Directories outside a git repository, or
+/// without any history yet, have no commits.
+fn read_commits(repo_root: &Path, relative_dir: &Path) -> Result<Vec<Commit>, SuggestOwnerError> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .arg("log")
+        .arg("--no-merges")
+        .arg("--format=%an%x09%ae%x09%ct")
+        .arg("--")
+        .arg(if relative_dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            relative_dir
+        })
+        .output()
+        .map_err(|source| SuggestOwnerError::FailedToRunGitLog { source })?;
+
+    if !output.status.success() {
+        return Ok(vec![]);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(Commit {
+                author_name: fields.next()?.to_string(),
+                author_email: fields.next()?.to_string(),
+                timestamp: fields.next()?.parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+/// Reads the mapping of author emails to entity refs, which is empty if the file is missing.
+fn read_mapping_file(path: &Path) -> Result<BTreeMap<String, String>, SuggestOwnerError> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let mapping_text = std::fs::read_to_string(path).map_err(|source| {
+        SuggestOwnerError::FailedToReadMappingFile {
+            path: format!("{}", path.display()),
+            source,
+        }
+    })?;
+    let mappings: BTreeMap<String, String> =
+        serde_yml::from_str(&mapping_text).map_err(|source| {
+            SuggestOwnerError::FailedToParseMappingFile {
+                path: format!("{}", path.display()),
+                source,
+            }
+        })?;
+
+    Ok(mappings
+        .into_iter()
+        .map(|(email, owner)| (email.to_lowercase(), owner))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+
+    fn commit(author_name: &str, author_email: &str, days: i64) -> Commit {
+        Commit {
+            author_name: author_name.to_string(),
+            author_email: author_email.to_string(),
+            timestamp: days * SECONDS_PER_DAY as i64,
+        }
+    }
+
+    #[test]
+    fn test_suggest_owner_from_commits() -> Result<()> {
+        let commits = vec![
+            commit("Alice", "alice@bxbdigital.com", 0),
+            commit("Alice", "alice@bxbdigital.com", 10),
+            commit("Alice", "alice@bxbdigital.com", 20),
+            commit("Bob", "bob@bxbdigital.com", 700),
+            commit("Carol", "Carol@bxbdigital.com", 720),
+            commit(
+                "dependabot[bot]",
+                "49699333+dependabot[bot]@users.noreply.github.com",
+                730,
+            ),
+            commit("renovate-bot", "bot@renovateapp.com", 730),
+        ];
+        let config = OwnersConfig::default();
+
+        // two recent commits outweigh three old ones
+        let suggestion = suggest_owner_from_commits(&commits, &BTreeMap::new(), &config)?;
+        assert_eq!(
+            suggestion.as_ref().map(|s| s.owner.as_str()),
+            Some("user:default/carol")
+        );
+        assert_eq!(suggestion.as_ref().map(|s| s.commits), Some(5));
+
+        // members of the same team add up
+        let mappings = BTreeMap::from([
+            (
+                "bob@bxbdigital.com".to_string(),
+                "group:default/payments".to_string(),
+            ),
+            (
+                "carol@bxbdigital.com".to_string(),
+                "group:default/payments".to_string(),
+            ),
+        ]);
+        let suggestion = suggest_owner_from_commits(&commits, &mappings, &config)?
+            .expect("an owner to be suggested");
+        assert_eq!(suggestion.owner, "group:default/payments");
+        assert!(suggestion.confidence > 0.9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_suggest_owner_from_bot_commits() -> Result<()> {
+        let commits = vec![commit("dependabot[bot]", "support@github.com", 0)];
+
+        let suggestion =
+            suggest_owner_from_commits(&commits, &BTreeMap::new(), &OwnersConfig::default())?;
+        assert_eq!(suggestion, None);
+
+        Ok(())
+    }
+}
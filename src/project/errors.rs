@@ -0,0 +1,25 @@
+use crate::docker::AnalyseDockerfilesError;
+use crate::languages::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::lockfile::ReadLockfileError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GetProjectDependenciesError {
+    #[error("Failed to get the internal dependencies of a {language} project: {source}")]
+    GetLanguageDependencies {
+        language: String,
+        source: GetInternalDependenciesError,
+    },
+
+    #[error("Failed to compile the internal repository and package patterns: {0}")]
+    CompileInternalPatterns(#[from] regex::Error),
+
+    #[error("Failed to read the project's lockfile: {0}")]
+    ReadLockfile(#[from] ReadLockfileError),
+
+    #[error("Failed to get the project's package names: {0}")]
+    GetPackageNames(#[from] GetMetadataError),
+
+    #[error("Failed to analyse the project's Dockerfiles: {0}")]
+    AnalyseDockerfiles(#[from] AnalyseDockerfilesError),
+}
@@ -1,20 +1,312 @@
+mod errors;
+
+use crate::backstage::{Component, DependencySource, SourceLocation, entity_name};
+use crate::config::{ComponentTypesConfig, Config};
+use crate::docker::DockerfileAnalysis;
+use crate::index::DependencyIndex;
 use crate::languages::Language;
-use std::path::PathBuf;
+use crate::languages::component_type::ComponentTypeInference;
+use crate::languages::errors::GetMetadataError;
+use crate::languages::metadata::ProjectMetadata;
+pub use errors::GetProjectDependenciesError;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub struct Project {
-    pub language: Box<dyn Language>,
+    /// The languages of the project, starting with the primary language
+    pub languages: Vec<Box<dyn Language>>,
     pub root: PathBuf,
 }
 
+/// An internal dependency of a project and everywhere it is declared.
+#[derive(Debug, Clone)]
+pub struct InternalDependency {
+    /// The entity ref of the component depended on, e.g. `component:default/payments`
+    pub entity_ref: String,
+
+    /// The lines of the project's files that declare the dependency
+    pub locations: Vec<SourceLocation>,
+
+    /// The distinct versions of the component the project requires
+    pub versions: Vec<String>,
+
+    /// The distinct places the component is fetched from
+    pub sources: Vec<DependencySource>,
+
+    /// The distinct versions built, from the project's lockfiles
+    pub resolved_versions: Vec<String>,
+
+    /// The distinct commits built, for components fetched from git
+    pub commits: Vec<String>,
+
+    /// Whether the project only depends on the component through other dependencies
+    pub indirect: bool,
+}
+
 impl Project {
-    pub fn new(root: PathBuf, language: Box<dyn Language>) -> Project {
-        Project { root, language }
+    pub fn new(root: PathBuf, languages: Vec<Box<dyn Language>>) -> Project {
+        Project { root, languages }
+    }
+
+    /// The language we are most confident the project is written in.
+    pub fn primary_language(&self) -> Option<&dyn Language> {
+        self.languages.first().map(|language| language.as_ref())
+    }
+
+    /// The tags describing the project's languages, primary language first.
+    pub fn get_language_tags(&self) -> Vec<String> {
+        self.languages
+            .iter()
+            .map(|language| language.to_string().to_lowercase())
+            .collect()
+    }
+
+    /// The metadata declared in the project's manifests, preferring the primary language's
+    /// manifest when several declare the same field.
+    pub fn get_metadata(&self) -> Result<ProjectMetadata, GetMetadataError> {
+        let mut metadata = ProjectMetadata::default();
+        for language in &self.languages {
+            metadata.merge(language.get_metadata(&self.root)?);
+        }
+        Ok(metadata)
+    }
+
+    /// The entity name of the project, which is `repo_name` for the project at the repository
+    /// root and the name of the project's directory otherwise.
+    pub fn get_name(&self, repo_root: &Path, repo_name: &str) -> String {
+        if self.root == repo_root {
+            return entity_name(repo_name);
+        }
+
+        self.root
+            .file_name()
+            .map(|name| entity_name(&name.to_string_lossy()))
+            .unwrap_or_else(|| entity_name(repo_name))
+    }
+
+    /// Infers the component type of the project named `name` from the first of its languages
+    /// with an opinion, falling back to `service`. Types set for the component or for the rule
+    /// that fired in `config` take precedence over the inferred type.
+    pub fn infer_component_type(
+        &self,
+        name: &str,
+        config: &ComponentTypesConfig,
+    ) -> ComponentTypeInference {
+        if let Some(r#type) = config.components.get(name) {
+            return ComponentTypeInference::new(r#type, "config-component");
+        }
+
+        let inference = self
+            .languages
+            .iter()
+            .find_map(|language| language.infer_component_type(&self.root))
+            .unwrap_or_else(|| ComponentTypeInference::new("service", "default"));
+
+        match config.rules.get(&inference.rule) {
+            Some(r#type) => ComponentTypeInference::new(r#type, &inference.rule),
+            None => inference,
+        }
+    }
+
+    /// The entity ref of the project's component.
+    pub fn get_entity_ref(&self, repo_root: &Path, repo_name: &str) -> String {
+        format!("component:default/{}", self.get_name(repo_root, repo_name))
+    }
+
+    /// The names the project is published under by each of its languages.
+    pub fn get_package_names(&self) -> Result<Vec<String>, GetMetadataError> {
+        let mut package_names = vec![];
+        for language in &self.languages {
+            for package_name in language.get_package_names(&self.root)? {
+                if !package_names.contains(&package_name) {
+                    package_names.push(package_name);
+                }
+            }
+        }
+        Ok(package_names)
+    }
+
+    /// Collects the internal dependencies declared by each of the project's languages and its
+    /// Dockerfiles, resolving them to entity refs through `index`. Dependencies are deduplicated
+    /// by entity ref, keeping the order they were first found in, and references to the project
+    /// itself (`self_ref`) are dropped. Each language's lockfile adds the versions built and the
+    /// internal dependencies the project only has through other dependencies.
+    pub fn get_internal_dependencies(
+        &self,
+        self_ref: &str,
+        config: &Config,
+        index: &DependencyIndex,
+    ) -> Result<Vec<InternalDependency>, GetProjectDependenciesError> {
+        let internal = config.internal.compile()?;
+        let package_names = self.get_package_names()?;
+        let mut components: Vec<Component> = vec![];
+        for language in &self.languages {
+            let mut language_components = language
+                .get_internal_dependencies(&self.root, &internal)
+                .map_err(
+                    |source| GetProjectDependenciesError::GetLanguageDependencies {
+                        language: language.to_string(),
+                        source,
+                    },
+                )?;
+
+            if let Some(lockfile) = language.get_lockfile(&self.root)? {
+                language_components =
+                    lockfile.lock(language_components, &package_names, &internal, index);
+            }
+            components.extend(language_components);
+        }
+        components.extend(
+            DockerfileAnalysis::analyse(&self.root, &config.docker, &internal)?
+                .get_internal_dependencies(),
+        );
+
+        let mut internal_dependencies: Vec<InternalDependency> = vec![];
+        for component in components {
+            let entity_ref = index.resolve(&component);
+            if entity_ref == self_ref {
+                continue;
+            }
+
+            let position = match internal_dependencies
+                .iter()
+                .position(|dependency| dependency.entity_ref == entity_ref)
+            {
+                Some(position) => position,
+                None => {
+                    internal_dependencies.push(InternalDependency {
+                        entity_ref,
+                        locations: vec![],
+                        versions: vec![],
+                        sources: vec![],
+                        resolved_versions: vec![],
+                        commits: vec![],
+                        indirect: true,
+                    });
+                    internal_dependencies.len() - 1
+                }
+            };
+
+            let dependency = &mut internal_dependencies[position];
+            dependency.indirect &= component.indirect;
+            if let Some(location) = component.location {
+                dependency.locations.push(location);
+            }
+            if let Some(version) = component.version
+                && !dependency.versions.contains(&version)
+            {
+                dependency.versions.push(version);
+            }
+            if let Some(source) = component.source
+                && !dependency.sources.contains(&source)
+            {
+                dependency.sources.push(source);
+            }
+            if let Some(version) = component.resolved_version
+                && !dependency.resolved_versions.contains(&version)
+            {
+                dependency.resolved_versions.push(version);
+            }
+            if let Some(commit) = component.commit
+                && !dependency.commits.contains(&commit)
+            {
+                dependency.commits.push(commit);
+            }
+        }
+
+        Ok(internal_dependencies)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::{go::Go, kubernetes::Kubernetes};
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_internal_dependencies() -> Result<()> {
+        let test_dir = tempdir()?;
+        let project_root = test_dir.path().join("orders");
+        std::fs::create_dir(&project_root)?;
+        std::fs::write(
+            project_root.join("go.mod"),
+            "module bitbucket.org/bxbdigital/orders\n\ngo 1.22\n\nrequire (\n\tbitbucket.org/bxbdigital/orders/v2 v2.0.0\n\tbitbucket.org/bxbdigital/ledger/v3 v3.1.0\n\tgolang.org/x/term v0.10.0\n)\n",
+        )?;
+        std::fs::write(
+            project_root.join("kustomization.yaml"),
+            "resources:\n  - https://bitbucket.org/bxbdigital/ledger//deploy?ref=v3\n",
+        )?;
+
+        let project = Project::new(
+            project_root.clone(),
+            vec![Box::new(Go::default()), Box::new(Kubernetes::default())],
+        );
+        let self_ref = project.get_entity_ref(test_dir.path(), "monorepo");
+        assert_eq!(self_ref, "component:default/orders");
+
+        let dependencies = project.get_internal_dependencies(
+            &self_ref,
+            &Config::default(),
+            &DependencyIndex::default(),
+        )?;
+
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].entity_ref, "component:default/ledger");
+        assert_eq!(
+            dependencies[0].locations,
+            vec![
+                SourceLocation {
+                    file: project_root.join("go.mod"),
+                    line: 7,
+                },
+                SourceLocation {
+                    file: project_root.join("kustomization.yaml"),
+                    line: 2,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_infer_component_type() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(test_dir.path().join("go.mod"), "module example.com/tool\n")?;
+        std::fs::write(
+            test_dir.path().join("main.go"),
+            "package main\n\nimport \"github.com/spf13/cobra\"\n\nfunc main() {}\n",
+        )?;
+
+        let project = Project::new(
+            test_dir.path().to_path_buf(),
+            vec![Box::new(Go::default()), Box::new(Kubernetes::default())],
+        );
+
+        let mut config = ComponentTypesConfig::default();
+        assert_eq!(
+            project.infer_component_type("tool", &config),
+            ComponentTypeInference::new("cli", "go-cli")
+        );
+
+        config
+            .rules
+            .insert("go-cli".to_string(), "tool".to_string());
+        assert_eq!(
+            project.infer_component_type("tool", &config),
+            ComponentTypeInference::new("tool", "go-cli")
+        );
+
+        config
+            .components
+            .insert("tool".to_string(), "website".to_string());
+        assert_eq!(
+            project.infer_component_type("tool", &config),
+            ComponentTypeInference::new("website", "config-component")
+        );
 
-    pub fn get_internal_dependencies(&self) -> Vec<String> {
-        let _internal_dependencies: Vec<String> = vec![];
-        // internal_dependencies
-        todo!();
+        Ok(())
     }
 }
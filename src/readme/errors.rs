@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReadReadmeError {
+    #[error("Failed to read the README {path}: {source}")]
+    FailedToReadReadme {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to compile README link regex: {source}")]
+    FailedToCompileLinkRegex { source: regex::Error },
+}
@@ -0,0 +1,429 @@
+mod errors;
+
+pub use errors::ReadReadmeError;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// The README file names we look for in a project root, in order of preference.
+const README_FILES: [&str; 5] = [
+    "README.md",
+    "README.markdown",
+    "README.rst",
+    "README.adoc",
+    "README.asciidoc",
+];
+
+/// The markup languages a README can be written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadmeFormat {
+    Markdown,
+    ReStructuredText,
+    AsciiDoc,
+}
+
+/// A link listed in a README's links or documentation section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadmeLink {
+    pub title: String,
+    pub url: String,
+}
+
+/// The metadata we can extract from a project's README.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Readme {
+    /// The first paragraph of prose, without markup
+    pub description: Option<String>,
+
+    /// The links in a "Links" or "Documentation" section
+    pub links: Vec<ReadmeLink>,
+}
+
+/// A block of a README, split on blank lines and headings.
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(String),
+    Paragraph(Vec<String>),
+
+    /// Lists, code, tables, HTML, images and directives, which never make a description
+    Other(Vec<String>),
+}
+
+impl Readme {
+    /// Reads the README in the project root, if there is one.
+    pub fn read(project_root: &Path) -> Result<Option<Readme>, ReadReadmeError> {
+        let Some((readme_path, format)) = find_readme(project_root) else {
+            return Ok(None);
+        };
+
+        let readme_text = std::fs::read_to_string(&readme_path).map_err(|source| {
+            ReadReadmeError::FailedToReadReadme {
+                path: format!("{}", readme_path.display()),
+                source,
+            }
+        })?;
+
+        Ok(Some(Readme::parse(&readme_text, format)?))
+    }
+
+    /// Extracts the description and links from the text of a README.
+    pub fn parse(text: &str, format: ReadmeFormat) -> Result<Readme, ReadReadmeError> {
+        let link_regex = Regex::new(match format {
+            ReadmeFormat::Markdown => r#"\[([^\]]+)\]\((https?://[^)\s]+)\)"#,
+            ReadmeFormat::ReStructuredText => r#"`([^`<]+?)\s*<(https?://[^>]+)>`__?"#,
+            ReadmeFormat::AsciiDoc => r#"(?:link:)?(https?://[^\s\[]+)\[([^\]]*)\]"#,
+        })
+        .map_err(|source| ReadReadmeError::FailedToCompileLinkRegex { source })?;
+        let bare_url_regex = Regex::new(r#"https?://[^\s<>()\[\]`]+"#)
+            .map_err(|source| ReadReadmeError::FailedToCompileLinkRegex { source })?;
+
+        let blocks = blocks(text, format);
+
+        let description = blocks.iter().find_map(|block| match block {
+            Block::Paragraph(lines) => {
+                let paragraph = plain_text(&lines.join(" "), &link_regex, format);
+                (!paragraph.is_empty()).then_some(paragraph)
+            }
+            _ => None,
+        });
+
+        let mut links: Vec<ReadmeLink> = vec![];
+        let mut in_links_section = false;
+        for block in &blocks {
+            let lines = match block {
+                Block::Heading(title) => {
+                    let title = title.to_lowercase();
+                    in_links_section = title.contains("links") || title == "documentation";
+                    continue;
+                }
+                Block::Paragraph(lines) | Block::Other(lines) => lines,
+            };
+            if !in_links_section {
+                continue;
+            }
+
+            for line in lines {
+                let mut found = false;
+                for link in link_regex.captures_iter(line) {
+                    let (title, url) = match format {
+                        ReadmeFormat::AsciiDoc => (link.get(2), link.get(1)),
+                        _ => (link.get(1), link.get(2)),
+                    };
+                    let url = url.map_or("", |m| m.as_str()).to_string();
+                    let title = title
+                        .map(|m| m.as_str().trim().to_string())
+                        .filter(|title| !title.is_empty())
+                        .unwrap_or_else(|| url.clone());
+                    links.push(ReadmeLink { title, url });
+                    found = true;
+                }
+
+                // a bare URL takes its title from the text before it, e.g. `- Runbook: https://...`
+                if !found && let Some(url) = bare_url_regex.find(line) {
+                    let title = line[..url.start()]
+                        .trim_start_matches(['-', '*', ' '])
+                        .trim()
+                        .trim_end_matches([':', '-'])
+                        .trim()
+                        .to_string();
+                    links.push(ReadmeLink {
+                        title: if title.is_empty() {
+                            url.as_str().to_string()
+                        } else {
+                            title
+                        },
+                        url: url.as_str().to_string(),
+                    });
+                }
+            }
+        }
+        links.dedup_by(|a, b| a.url == b.url);
+
+        Ok(Readme { description, links })
+    }
+}
+
+/// Finds the README in a project root and the format it is written in.
+pub fn find_readme(project_root: &Path) -> Option<(PathBuf, ReadmeFormat)> {
+    let file_names: Vec<String> = std::fs::read_dir(project_root)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+
+    // README file names are matched case-insensitively, e.g. `Readme.md`
+    README_FILES.iter().find_map(|readme_file| {
+        let file_name = file_names
+            .iter()
+            .find(|file_name| file_name.eq_ignore_ascii_case(readme_file))?;
+        let format = match readme_file.rsplit('.').next() {
+            Some("rst") => ReadmeFormat::ReStructuredText,
+            Some("adoc" | "asciidoc") => ReadmeFormat::AsciiDoc,
+            _ => ReadmeFormat::Markdown,
+        };
+        Some((project_root.join(file_name), format))
+    })
+}
+
+/// Splits a README into headings, paragraphs and everything else.
+fn blocks(text: &str, format: ReadmeFormat) -> Vec<Block> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut blocks = vec![];
+    let mut current: Vec<String> = vec![];
+    let mut in_code = false;
+
+    let flush = |current: &mut Vec<String>, blocks: &mut Vec<Block>| {
+        if current.is_empty() {
+            return;
+        }
+        let block = std::mem::take(current);
+        if block.iter().all(|line| is_prose(line, format)) {
+            blocks.push(Block::Paragraph(block));
+        } else {
+            blocks.push(Block::Other(block));
+        }
+    };
+
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim();
+        let next = lines.get(index + 1).map(|next| next.trim());
+
+        // fenced and delimited code blocks
+        let fence = match format {
+            ReadmeFormat::Markdown => trimmed.starts_with("```") || trimmed.starts_with("~~~"),
+            ReadmeFormat::AsciiDoc => trimmed == "----" || trimmed == "....",
+            ReadmeFormat::ReStructuredText => false,
+        };
+        if fence || in_code {
+            if fence {
+                in_code = !in_code;
+            }
+            current.push(line.to_string());
+            if !in_code {
+                flush(&mut current, &mut blocks);
+            }
+            index += 1;
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush(&mut current, &mut blocks);
+            index += 1;
+            continue;
+        }
+
+        let heading = match format {
+            ReadmeFormat::Markdown if trimmed.starts_with('#') => {
+                Some(trimmed.trim_start_matches('#').trim_end_matches('#').trim())
+            }
+            ReadmeFormat::AsciiDoc if trimmed.starts_with('=') => {
+                Some(trimmed.trim_start_matches('=').trim())
+            }
+            // overlines and horizontal rules are skipped, and titles are recognised by their
+            // underline
+            _ if is_underline(trimmed) => {
+                flush(&mut current, &mut blocks);
+                index += 1;
+                continue;
+            }
+            _ if current.is_empty() && next.is_some_and(is_underline) => {
+                // an underline of `-` under a Markdown paragraph line is a setext heading
+                index += 1;
+                Some(trimmed)
+            }
+            _ => None,
+        };
+        if let Some(heading) = heading {
+            flush(&mut current, &mut blocks);
+            blocks.push(Block::Heading(heading.to_string()));
+            index += 1;
+            continue;
+        }
+
+        current.push(line.to_string());
+        index += 1;
+    }
+    flush(&mut current, &mut blocks);
+
+    blocks
+}
+
+/// Checks if a line is a heading underline such as `=====` or `-----`.
+fn is_underline(line: &str) -> bool {
+    line.len() >= 3
+        && line
+            .chars()
+            .all(|c| matches!(c, '=' | '-' | '~' | '^' | '*' | '+' | '#' | '"' | '`'))
+        && line.chars().all(|c| line.starts_with(c))
+}
+
+/// Checks if a line is plain prose, rather than a badge, image, HTML, a list item, a table row,
+/// or a directive or attribute.
+fn is_prose(line: &str, format: ReadmeFormat) -> bool {
+    let trimmed = line.trim();
+    let markup = match format {
+        ReadmeFormat::Markdown => {
+            trimmed.starts_with("[![")
+                || trimmed.starts_with("![")
+                || trimmed.starts_with('>')
+                || line.starts_with("    ")
+        }
+        ReadmeFormat::ReStructuredText => trimmed.starts_with("..") || line.starts_with(' '),
+        ReadmeFormat::AsciiDoc => {
+            trimmed.starts_with("image:")
+                || trimmed.starts_with(':')
+                || trimmed.starts_with("//")
+                || trimmed.starts_with('[')
+        }
+    };
+
+    !markup
+        && !trimmed.starts_with('<')
+        && !trimmed.starts_with('|')
+        && !trimmed.starts_with("- ")
+        && !trimmed.starts_with("* ")
+        && !trimmed.starts_with("+ ")
+        && !trimmed
+            .split_once(". ")
+            .is_some_and(|(number, _)| number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Removes inline markup from a paragraph, keeping the text of links.
+fn plain_text(paragraph: &str, link_regex: &Regex, format: ReadmeFormat) -> String {
+    let text = link_regex.replace_all(
+        paragraph,
+        match format {
+            ReadmeFormat::AsciiDoc => "$2",
+            _ => "$1",
+        },
+    );
+
+    text.replace("**", "")
+        .replace("__", "")
+        .replace("``", "")
+        .replace('`', "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_markdown() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("Readme.md"),
+            r#"# Payments
+
+[![Build](https://ci.example.com/badge.svg)](https://ci.example.com)
+<p align="center"><img src="logo.png"></p>
+
+The **payments** service takes card payments for the [shop](https://shop.example.com)
+and settles them with `ledger`.
+
+## Usage
+
+Run `make up`.
+
+## Useful Links
+
+- [Runbook](https://wiki.example.com/payments/runbook)
+- Dashboards: https://grafana.example.com/d/payments
+"#,
+        )?;
+
+        let readme = Readme::read(test_dir.path())?.expect("a README to be found");
+
+        assert_eq!(
+            readme.description.as_deref(),
+            Some(
+                "The payments service takes card payments for the shop and settles them with ledger."
+            )
+        );
+        assert_eq!(
+            readme.links,
+            vec![
+                ReadmeLink {
+                    title: "Runbook".to_string(),
+                    url: "https://wiki.example.com/payments/runbook".to_string(),
+                },
+                ReadmeLink {
+                    title: "Dashboards".to_string(),
+                    url: "https://grafana.example.com/d/payments".to_string(),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_restructured_text() -> Result<()> {
+        let readme = Readme::parse(
+            r#"========
+Payments
+========
+
+.. image:: https://ci.example.com/badge.svg
+   :target: https://ci.example.com
+
+Takes card payments.
+
+Documentation
+-------------
+
+See the `user guide <https://docs.example.com/payments>`_.
+"#,
+            ReadmeFormat::ReStructuredText,
+        )?;
+
+        assert_eq!(readme.description.as_deref(), Some("Takes card payments."));
+        assert_eq!(
+            readme.links,
+            vec![ReadmeLink {
+                title: "user guide".to_string(),
+                url: "https://docs.example.com/payments".to_string(),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_asciidoc() -> Result<()> {
+        let readme = Readme::parse(
+            r#"= Payments
+:toc:
+
+image:https://ci.example.com/badge.svg[Build]
+
+Takes card payments for the https://shop.example.com[shop].
+
+== Links
+
+* https://wiki.example.com/payments[Wiki]
+"#,
+            ReadmeFormat::AsciiDoc,
+        )?;
+
+        assert_eq!(
+            readme.description.as_deref(),
+            Some("Takes card payments for the shop.")
+        );
+        assert_eq!(
+            readme.links,
+            vec![ReadmeLink {
+                title: "Wiki".to_string(),
+                url: "https://wiki.example.com/payments".to_string(),
+            }]
+        );
+
+        Ok(())
+    }
+}
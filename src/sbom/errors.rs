@@ -0,0 +1,21 @@
+use crate::languages::errors::{GetInternalDependenciesError, GetMetadataError};
+use crate::lockfile::ReadLockfileError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BuildSbomError {
+    #[error("Failed to get the dependencies of a {language} project: {source}")]
+    GetLanguageDependencies {
+        language: String,
+        source: GetInternalDependenciesError,
+    },
+
+    #[error("Failed to compile the internal repository and package patterns: {0}")]
+    CompileInternalPatterns(#[from] regex::Error),
+
+    #[error("Failed to read the project's lockfile: {0}")]
+    ReadLockfile(#[from] ReadLockfileError),
+
+    #[error("Failed to get the project's metadata: {0}")]
+    GetMetadata(#[from] GetMetadataError),
+}
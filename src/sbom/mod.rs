@@ -0,0 +1,609 @@
+mod errors;
+
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::index::DependencyIndex;
+use crate::project::Project;
+pub use errors::BuildSbomError;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The property or external reference recording a component's Backstage entity ref.
+const ENTITY_REF_PROPERTY: &str = "backstage:entityRef";
+
+/// The formats a software bill of materials can be written in.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+
+    /// SPDX 2.3 JSON
+    Spdx,
+
+    /// Both CycloneDX and SPDX
+    All,
+}
+
+/// A package a project depends on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbomPackage {
+    pub name: String,
+
+    /// The version built, or the version required when it is exact
+    pub version: Option<String>,
+
+    /// The package URL, e.g. `pkg:cargo/serde@1.0.200`
+    pub purl: Option<String>,
+
+    /// The commit built, for packages fetched from git
+    pub commit: Option<String>,
+
+    /// Whether the project only depends on the package through other packages
+    pub indirect: bool,
+
+    /// The entity ref of the component publishing the package, if it is one of ours
+    pub entity_ref: Option<String>,
+}
+
+/// The software bill of materials of a project: the project and every package it depends on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSbom {
+    pub name: String,
+    pub repo_name: String,
+    pub entity_ref: String,
+    pub version: Option<String>,
+    pub purl: Option<String>,
+    pub packages: Vec<SbomPackage>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxBom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: CycloneDxMetadata,
+    components: Vec<CycloneDxComponent>,
+    dependencies: Vec<CycloneDxDependency>,
+}
+
+#[derive(Serialize, Debug)]
+struct CycloneDxMetadata {
+    timestamp: String,
+    component: CycloneDxComponent,
+}
+
+#[derive(Serialize, Debug)]
+struct CycloneDxComponent {
+    #[serde(rename = "type")]
+    kind: &'static str,
+
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+
+    name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<CycloneDxProperty>,
+}
+
+#[derive(Serialize, Debug)]
+struct CycloneDxProperty {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize, Debug)]
+struct CycloneDxDependency {
+    #[serde(rename = "ref")]
+    reference: String,
+
+    #[serde(rename = "dependsOn")]
+    depends_on: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxDocument {
+    spdx_version: &'static str,
+    data_license: &'static str,
+
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+
+    name: String,
+    document_namespace: String,
+    creation_info: SpdxCreationInfo,
+    packages: Vec<SpdxPackage>,
+    relationships: Vec<SpdxRelationship>,
+}
+
+#[derive(Serialize, Debug)]
+struct SpdxCreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+
+    name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version_info: Option<String>,
+
+    download_location: &'static str,
+    files_analyzed: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_refs: Vec<SpdxExternalRef>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SpdxExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SpdxRelationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+impl ProjectSbom {
+    /// Lists every package the project declares in its manifests, at the versions pinned by its
+    /// lockfiles, along with the packages it only depends on through them.
+    pub fn for_project(
+        repo: &GitRepo,
+        repo_name: &str,
+        project: &Project,
+        config: &Config,
+        index: &DependencyIndex,
+    ) -> Result<ProjectSbom, BuildSbomError> {
+        let internal = config.internal.compile()?;
+        let package_names = project.get_package_names()?;
+        let metadata = project.get_metadata()?;
+
+        let mut packages: Vec<SbomPackage> = vec![];
+        for language in &project.languages {
+            let mut components = language
+                .get_all_dependencies(&project.root, &internal)
+                .map_err(|source| BuildSbomError::GetLanguageDependencies {
+                    language: language.to_string(),
+                    source,
+                })?;
+            if let Some(lockfile) = language.get_lockfile(&project.root)? {
+                components = lockfile.lock_all(components, &package_names, &internal);
+            }
+
+            for component in components {
+                let version = component
+                    .resolved_version
+                    .or_else(|| component.version.as_deref().and_then(exact_version));
+                if packages
+                    .iter()
+                    .any(|package| package.name == component.name && package.version == version)
+                {
+                    continue;
+                }
+
+                packages.push(SbomPackage {
+                    purl: language
+                        .purl_type()
+                        .map(|purl_type| purl(purl_type, &component.name, version.as_deref())),
+                    entity_ref: index.get(&component.name).cloned(),
+                    name: component.name,
+                    version,
+                    commit: component.commit,
+                    indirect: component.indirect,
+                });
+            }
+        }
+
+        let purl = project
+            .primary_language()
+            .and_then(|language| language.purl_type())
+            .zip(package_names.first())
+            .map(|(purl_type, name)| purl(purl_type, name, metadata.version.as_deref()));
+
+        Ok(ProjectSbom {
+            name: project.get_name(&repo.root, repo_name),
+            repo_name: repo_name.to_string(),
+            entity_ref: project.get_entity_ref(&repo.root, repo_name),
+            version: metadata.version,
+            purl,
+            packages,
+        })
+    }
+
+    /// The bill of materials as a CycloneDX 1.5 document created at `timestamp`.
+    pub fn to_cyclonedx(&self, timestamp: &str) -> CycloneDxBom {
+        let bom_ref = |package: &SbomPackage| {
+            package
+                .purl
+                .clone()
+                .unwrap_or_else(|| match &package.version {
+                    Some(version) => format!("{}@{version}", package.name),
+                    None => package.name.clone(),
+                })
+        };
+
+        let components: Vec<CycloneDxComponent> = self
+            .packages
+            .iter()
+            .map(|package| {
+                let mut properties = vec![];
+                if let Some(entity_ref) = &package.entity_ref {
+                    properties.push(CycloneDxProperty {
+                        name: ENTITY_REF_PROPERTY.to_string(),
+                        value: entity_ref.clone(),
+                    });
+                }
+                if let Some(commit) = &package.commit {
+                    properties.push(CycloneDxProperty {
+                        name: "metaforge:commit".to_string(),
+                        value: commit.clone(),
+                    });
+                }
+                if package.indirect {
+                    properties.push(CycloneDxProperty {
+                        name: "metaforge:indirect".to_string(),
+                        value: "true".to_string(),
+                    });
+                }
+
+                CycloneDxComponent {
+                    kind: "library",
+                    bom_ref: bom_ref(package),
+                    name: package.name.clone(),
+                    version: package.version.clone(),
+                    purl: package.purl.clone(),
+                    properties,
+                }
+            })
+            .collect();
+
+        // indirect packages are reached through packages whose dependencies we don't know
+        let depends_on = self
+            .packages
+            .iter()
+            .filter(|package| !package.indirect)
+            .map(bom_ref)
+            .collect();
+
+        CycloneDxBom {
+            bom_format: "CycloneDX",
+            spec_version: "1.5",
+            version: 1,
+            metadata: CycloneDxMetadata {
+                timestamp: timestamp.to_string(),
+                component: CycloneDxComponent {
+                    kind: "application",
+                    bom_ref: self.entity_ref.clone(),
+                    name: self.name.clone(),
+                    version: self.version.clone(),
+                    purl: self.purl.clone(),
+                    properties: vec![CycloneDxProperty {
+                        name: ENTITY_REF_PROPERTY.to_string(),
+                        value: self.entity_ref.clone(),
+                    }],
+                },
+            },
+            components,
+            dependencies: vec![CycloneDxDependency {
+                reference: self.entity_ref.clone(),
+                depends_on,
+            }],
+        }
+    }
+
+    /// The bill of materials as an SPDX 2.3 document created at `timestamp`.
+    pub fn to_spdx(&self, timestamp: &str) -> SpdxDocument {
+        let external_refs = |purl: &Option<String>, entity_ref: &Option<String>| {
+            let mut external_refs = vec![];
+            if let Some(purl) = purl {
+                external_refs.push(SpdxExternalRef {
+                    reference_category: "PACKAGE-MANAGER",
+                    reference_type: "purl",
+                    reference_locator: purl.clone(),
+                });
+            }
+            if let Some(entity_ref) = entity_ref {
+                external_refs.push(SpdxExternalRef {
+                    reference_category: "OTHER",
+                    reference_type: ENTITY_REF_PROPERTY,
+                    reference_locator: entity_ref.clone(),
+                });
+            }
+            external_refs
+        };
+
+        let root_id = format!("SPDXRef-{}", spdx_id(&self.name));
+        let mut packages = vec![SpdxPackage {
+            spdx_id: root_id.clone(),
+            name: self.name.clone(),
+            version_info: self.version.clone(),
+            download_location: "NOASSERTION",
+            files_analyzed: false,
+            comment: None,
+            external_refs: external_refs(&self.purl, &Some(self.entity_ref.clone())),
+        }];
+        let mut relationships = vec![SpdxRelationship {
+            spdx_element_id: "SPDXRef-DOCUMENT".to_string(),
+            relationship_type: "DESCRIBES",
+            related_spdx_element: root_id.clone(),
+        }];
+
+        for (number, package) in self.packages.iter().enumerate() {
+            let package_id = format!("SPDXRef-Package-{}-{}", number + 1, spdx_id(&package.name));
+            // as in CycloneDX, indirect packages are left unrelated, as the packages they are
+            // reached through aren't known
+            if !package.indirect {
+                relationships.push(SpdxRelationship {
+                    spdx_element_id: root_id.clone(),
+                    relationship_type: "DEPENDS_ON",
+                    related_spdx_element: package_id.clone(),
+                });
+            }
+            packages.push(SpdxPackage {
+                spdx_id: package_id,
+                name: package.name.clone(),
+                version_info: package.version.clone(),
+                download_location: "NOASSERTION",
+                files_analyzed: false,
+                comment: package
+                    .indirect
+                    .then(|| "An indirect dependency".to_string()),
+                external_refs: external_refs(&package.purl, &package.entity_ref),
+            });
+        }
+
+        SpdxDocument {
+            spdx_version: "SPDX-2.3",
+            data_license: "CC0-1.0",
+            spdx_id: "SPDXRef-DOCUMENT",
+            name: self.name.clone(),
+            document_namespace: format!(
+                "https://metaforge.io/spdx/{}/{}-{timestamp}",
+                self.repo_name, self.name
+            ),
+            creation_info: SpdxCreationInfo {
+                created: timestamp.to_string(),
+                creators: vec!["Tool: metaforge".to_string()],
+            },
+            packages,
+            relationships,
+        }
+    }
+}
+
+/// The package URL of a package, e.g. `pkg:npm/%40bxbdigital/ui@1.2.0` or
+/// `pkg:maven/com.bxbdigital/ledger@2.0.1`.
+pub fn purl(purl_type: &str, name: &str, version: Option<&str>) -> String {
+    let name = match purl_type {
+        "maven" => name.replacen(':', "/", 1),
+        "pypi" => name.to_lowercase().replace('_', "-"),
+        _ => name.to_string(),
+    };
+    let name = name.replace('@', "%40");
+
+    match version {
+        Some(version) => format!(
+            "pkg:{purl_type}/{name}@{}",
+            version.replace('+', "%2B").replace('@', "%40")
+        ),
+        None => format!("pkg:{purl_type}/{name}"),
+    }
+}
+
+/// The version a requirement pins, e.g. `1.2.0` for `==1.2.0`, or `None` for a range such as
+/// `^1.2.0` or `>=1.0,<2`.
+fn exact_version(requirement: &str) -> Option<String> {
+    let version = requirement.trim().trim_start_matches('=');
+    let starts_like_version = version
+        .trim_start_matches('v')
+        .starts_with(|c: char| c.is_ascii_digit());
+    let is_range = version.contains(['^', '~', '<', '>', '*', '|', ',', ' ', 'x', 'X']);
+    // a bare `1.2` is a range in most package managers, so only full versions are exact
+    let is_full = version.matches('.').count() >= 2;
+    (starts_like_version && !is_range && is_full).then(|| version.to_string())
+}
+
+/// Turns a name into the characters allowed in an SPDX identifier.
+fn spdx_id(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// The current time in UTC as an RFC 3339 timestamp, e.g. `2024-05-01T12:30:00Z`.
+pub fn timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let (days, seconds_of_day) = ((seconds / 86_400) as i64, seconds % 86_400);
+
+    // converts days since the epoch to a civil date, see
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_repo;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_for_project() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+        std::fs::write(
+            test_dir.path().join("Cargo.toml"),
+            r#"[package]
+name = "checkout"
+version = "0.3.0"
+
+[dependencies]
+serde = "1"
+ledger = { git = "ssh://git@bitbucket.org/bxbdigital/ledger.git", tag = "v1.4.0" }
+"#,
+        )?;
+        std::fs::write(
+            test_dir.path().join("Cargo.lock"),
+            r#"[[package]]
+name = "checkout"
+version = "0.3.0"
+dependencies = ["ledger", "serde"]
+
+[[package]]
+name = "ledger"
+version = "1.4.0"
+source = "git+ssh://git@bitbucket.org/bxbdigital/ledger.git?tag=v1.4.0#0123456789abcdef"
+dependencies = ["money"]
+
+[[package]]
+name = "money"
+version = "0.3.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )?;
+
+        let scanned = scan_repo(Some(test_dir.path().into())).await?;
+        let mut index = DependencyIndex::default();
+        index.insert("ledger", "component:default/ledger");
+
+        let sbom = ProjectSbom::for_project(
+            &scanned.repo,
+            "checkout",
+            &scanned.repo.projects[0],
+            &scanned.config,
+            &index,
+        )?;
+
+        let packages: Vec<(&str, Option<&str>, bool, Option<&str>)> = sbom
+            .packages
+            .iter()
+            .map(|package| {
+                (
+                    package.purl.as_deref().unwrap_or_default(),
+                    package.commit.as_deref(),
+                    package.indirect,
+                    package.entity_ref.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            packages,
+            vec![
+                (
+                    "pkg:cargo/ledger@1.4.0",
+                    Some("0123456789abcdef"),
+                    false,
+                    Some("component:default/ledger")
+                ),
+                ("pkg:cargo/serde@1.0.200", None, false, None),
+                ("pkg:cargo/money@0.3.1", None, true, None),
+            ]
+        );
+        assert_eq!(sbom.purl.as_deref(), Some("pkg:cargo/checkout@0.3.0"));
+
+        let cyclonedx = serde_json::to_value(sbom.to_cyclonedx("2024-05-01T12:30:00Z"))?;
+        assert_eq!(
+            cyclonedx["metadata"]["component"]["properties"][0]["value"],
+            "component:default/checkout"
+        );
+        assert_eq!(
+            cyclonedx["dependencies"][0]["dependsOn"],
+            serde_json::json!(["pkg:cargo/ledger@1.4.0", "pkg:cargo/serde@1.0.200"])
+        );
+
+        let spdx = serde_json::to_value(sbom.to_spdx("2024-05-01T12:30:00Z"))?;
+        assert_eq!(
+            spdx["packages"].as_array().map(|packages| packages.len()),
+            Some(4)
+        );
+        assert_eq!(
+            spdx["relationships"][0]["relatedSpdxElement"],
+            "SPDXRef-checkout"
+        );
+        let depends_on: Vec<&str> = spdx["relationships"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|relationship| relationship["relationshipType"] == "DEPENDS_ON")
+            .filter_map(|relationship| relationship["relatedSpdxElement"].as_str())
+            .collect();
+        assert_eq!(
+            depends_on,
+            vec!["SPDXRef-Package-1-ledger", "SPDXRef-Package-2-serde"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_purl() {
+        assert_eq!(
+            purl("npm", "@bxbdigital/ui", Some("1.2.0")),
+            "pkg:npm/%40bxbdigital/ui@1.2.0"
+        );
+        assert_eq!(
+            purl("maven", "com.bxbdigital:ledger", None),
+            "pkg:maven/com.bxbdigital/ledger"
+        );
+        assert_eq!(exact_version("==2.31.0"), Some("2.31.0".to_string()));
+        assert_eq!(exact_version("^1.2.0"), None);
+    }
+}
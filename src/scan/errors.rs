@@ -0,0 +1,47 @@
+use crate::apis::FindApisError;
+use crate::backstage::ProjectCatalogInfoError;
+use crate::codeowners::LoadCodeOwnersError;
+use crate::config::LoadConfigError;
+use crate::git::{FindProjectsError, GitRepoTryFromError};
+use crate::languages::errors::GetMetadataError;
+use crate::project::GetProjectDependenciesError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScanRepoError {
+    #[error("Failed to find the git repository: {0}")]
+    FindGitRepo(#[from] GitRepoTryFromError),
+
+    #[error("Failed to find the repository's projects: {0}")]
+    FindProjects(#[from] FindProjectsError),
+
+    #[error("Failed to find the repository's APIs: {0}")]
+    FindApis(#[from] FindApisError),
+
+    #[error("Failed to load the repository's config: {0}")]
+    LoadConfig(#[from] LoadConfigError),
+
+    #[error("Failed to compile the internal repository and package patterns: {0}")]
+    CompileInternalPatterns(#[from] regex::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum CatalogDocumentsError {
+    #[error("Failed to create a project's catalog info: {0}")]
+    ProjectCatalogInfo(#[from] ProjectCatalogInfoError),
+
+    #[error("Failed to load the repository's CODEOWNERS file: {0}")]
+    LoadCodeOwners(#[from] LoadCodeOwnersError),
+
+    #[error("Failed to serialise a catalog entity: {0}")]
+    SerialiseEntity(#[from] serde_yml::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum ScanReportError {
+    #[error("Failed to get a project's package names: {0}")]
+    GetPackageNames(#[from] GetMetadataError),
+
+    #[error("Failed to get a project's internal dependencies: {0}")]
+    GetInternalDependencies(#[from] GetProjectDependenciesError),
+}
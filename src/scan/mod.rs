@@ -0,0 +1,352 @@
+mod errors;
+
+use crate::backstage::{CatalogInfo, Domain, System};
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::index::DependencyIndex;
+use crate::layout::ProjectDocuments;
+pub use errors::{CatalogDocumentsError, ScanRepoError, ScanReportError};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// A repository whose projects and APIs have been found.
+#[derive(Debug)]
+pub struct ScannedRepo {
+    pub repo: GitRepo,
+
+    /// The name of the repository, from its origin remote or else its root directory
+    pub name: String,
+
+    pub config: Config,
+}
+
+/// A repository that could not be scanned.
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// What was found in each scanned repository.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ScanReport {
+    pub repos: Vec<RepoReport>,
+    pub failures: Vec<ScanFailure>,
+}
+
+/// What was found in a single repository.
+#[derive(Serialize, Debug, Clone)]
+pub struct RepoReport {
+    pub name: String,
+    pub path: PathBuf,
+
+    /// The entity refs of the repository's projects
+    pub projects: Vec<String>,
+
+    /// Dependencies that don't resolve to any scanned project
+    #[serde(rename = "unresolvedDependencies")]
+    pub unresolved_dependencies: Vec<String>,
+}
+
+/// Finds the projects, APIs and config of the git repository containing `path`, or the current
+/// directory when no path is given.
+pub async fn scan_repo(path: Option<PathBuf>) -> Result<ScannedRepo, ScanRepoError> {
+    let mut repo = GitRepo::try_from(path).await?;
+    repo.find_projects().await?;
+
+    let config = Config::load(&repo.root)?;
+    repo.find_apis(&config.internal.compile()?).await?;
+
+    // repositories without an origin remote are named after their root directory
+    let name = match repo.get_origin_remote_repo_name().await {
+        Ok(name) => name,
+        Err(_) => repo
+            .root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+
+    Ok(ScannedRepo { repo, name, config })
+}
+
+/// Finds the git repositories under `dir`, without looking inside the repositories themselves
+/// or following symlinks.
+pub fn find_git_repos(dir: &Path) -> Vec<PathBuf> {
+    let mut repos = vec![];
+    let mut directories = vec![dir.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        if directory.join(".git").exists() {
+            repos.push(directory);
+            continue;
+        }
+
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+                && !file_name.starts_with('.')
+                && file_name != "node_modules"
+            {
+                directories.push(path);
+            }
+        }
+    }
+
+    repos.sort();
+    repos
+}
+
+/// Scans every git repository under `dir`, `concurrency` at a time. Repositories that fail to
+/// scan are reported rather than stopping the others.
+pub async fn scan_all(dir: &Path, concurrency: usize) -> (Vec<ScannedRepo>, Vec<ScanFailure>) {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut scans = JoinSet::new();
+
+    let mut paths = HashMap::new();
+    for path in find_git_repos(dir) {
+        let semaphore = semaphore.clone();
+        let task_path = path.clone();
+        let task = scans.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            scan_repo(Some(task_path)).await
+        });
+        paths.insert(task.id(), path);
+    }
+
+    let mut repos = vec![];
+    let mut failures = vec![];
+    while let Some(scan) = scans.join_next_with_id().await {
+        match scan {
+            Ok((_, Ok(scanned))) => repos.push(scanned),
+            Ok((id, Err(error))) => failures.push(ScanFailure {
+                path: paths.remove(&id).unwrap_or_else(|| dir.to_path_buf()),
+                error: error.to_string(),
+            }),
+            // a panicking scan still names the repository it was scanning
+            Err(error) => failures.push(ScanFailure {
+                path: paths
+                    .remove(&error.id())
+                    .unwrap_or_else(|| dir.to_path_buf()),
+                error: error.to_string(),
+            }),
+        }
+    }
+
+    repos.sort_by(|a, b| a.repo.root.cmp(&b.repo.root));
+    failures.sort_by(|a, b| a.path.cmp(&b.path));
+    (repos, failures)
+}
+
+/// Creates the YAML documents of the repository's System, Domain, components and APIs.
+pub fn catalog_documents(
+    scanned: &ScannedRepo,
+    index: &DependencyIndex,
+) -> Result<Vec<ProjectDocuments>, CatalogDocumentsError> {
+    let ScannedRepo { repo, name, config } = scanned;
+    let mut projects: Vec<ProjectDocuments> = vec![];
+
+    // the System and Domain are declared alongside the repository root's entities
+    if let Some(system) = System::for_repo(repo, name, config)? {
+        let mut documents = vec![serde_yml::to_string(&system)?];
+        if let Some(domain) = Domain::for_system(&system, config) {
+            documents.push(serde_yml::to_string(&domain)?);
+        }
+        projects.push(ProjectDocuments {
+            project_root: repo.root.clone(),
+            documents,
+        });
+    }
+
+    for project in &repo.projects {
+        let catalog_info = CatalogInfo::for_project(repo, name, project, config, index)?;
+        let catalog_dir = config.layout.catalog_dir(&repo.root, &project.root);
+        let mut documents = vec![serde_yml::to_string(&catalog_info)?];
+
+        for api in repo.get_project_apis(project) {
+            let api_catalog_info = CatalogInfo::for_api(api, &catalog_dir, &catalog_info);
+            documents.push(serde_yml::to_string(&api_catalog_info)?);
+        }
+
+        projects.push(ProjectDocuments {
+            project_root: project.root.clone(),
+            documents,
+        });
+    }
+
+    Ok(projects)
+}
+
+/// Builds the index of every package published by the scanned repositories.
+pub fn build_index(repos: &[ScannedRepo]) -> Result<DependencyIndex, ScanReportError> {
+    let mut index = DependencyIndex::default();
+    for scanned in repos {
+        index.add_repo(&scanned.repo, &scanned.name)?;
+    }
+    Ok(index)
+}
+
+impl ScanReport {
+    /// Reports the projects of each repository and the dependencies that don't resolve to any
+    /// of the scanned projects.
+    pub fn new(
+        repos: &[ScannedRepo],
+        failures: Vec<ScanFailure>,
+        index: &DependencyIndex,
+    ) -> Result<ScanReport, ScanReportError> {
+        let known: BTreeSet<String> = repos
+            .iter()
+            .flat_map(|scanned| {
+                scanned
+                    .repo
+                    .projects
+                    .iter()
+                    .map(|project| project.get_entity_ref(&scanned.repo.root, &scanned.name))
+            })
+            .collect();
+
+        let mut repo_reports = vec![];
+        for scanned in repos {
+            let mut projects = vec![];
+            let mut unresolved_dependencies = vec![];
+            for project in &scanned.repo.projects {
+                let self_ref = project.get_entity_ref(&scanned.repo.root, &scanned.name);
+                for dependency in project
+                    .get_internal_dependencies(&self_ref, &scanned.config, index)?
+                    .into_iter()
+                    .filter(|dependency| !dependency.indirect)
+                {
+                    if !known.contains(&dependency.entity_ref)
+                        && !unresolved_dependencies.contains(&dependency.entity_ref)
+                    {
+                        unresolved_dependencies.push(dependency.entity_ref);
+                    }
+                }
+                projects.push(self_ref);
+            }
+
+            repo_reports.push(RepoReport {
+                name: scanned.name.clone(),
+                path: scanned.repo.root.clone(),
+                projects,
+                unresolved_dependencies,
+            });
+        }
+
+        Ok(ScanReport {
+            repos: repo_reports,
+            failures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_scan_all() -> Result<()> {
+        let test_dir = tempdir()?;
+        let orders = test_dir.path().join("team-a").join("orders");
+        let ledger = test_dir.path().join("ledger-service");
+        let broken = test_dir.path().join("broken");
+        for repo in [&orders, &ledger, &broken] {
+            std::fs::create_dir_all(repo.join(".git"))?;
+        }
+
+        std::fs::write(
+            orders.join("go.mod"),
+            "module bitbucket.org/bxbdigital/orders\n\nrequire (\n\tbitbucket.org/bxbdigital/ledger-go/v2 v2.0.0\n\tbitbucket.org/bxbdigital/missing v1.0.0\n)\n",
+        )?;
+        std::fs::write(
+            ledger.join("go.mod"),
+            "module bitbucket.org/bxbdigital/ledger-go\n",
+        )?;
+        std::fs::write(broken.join(".metaforge.yaml"), "docker: [")?;
+
+        let (repos, failures) = scan_all(test_dir.path(), 2).await;
+        assert_eq!(repos.len(), 2);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].path, broken);
+
+        let index = build_index(&repos)?;
+        let report = ScanReport::new(&repos, failures, &index)?;
+
+        assert_eq!(report.repos[1].name, "orders");
+        assert_eq!(
+            report.repos[1].unresolved_dependencies,
+            vec!["component:default/missing"]
+        );
+
+        let self_ref = repos[1].repo.projects[0].get_entity_ref(&orders, "orders");
+        let dependencies = repos[1].repo.projects[0].get_internal_dependencies(
+            &self_ref,
+            &repos[1].config,
+            &index,
+        )?;
+        assert_eq!(
+            dependencies[0].entity_ref,
+            "component:default/ledger-service"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_build_index_scoped_packages() -> Result<()> {
+        let test_dir = tempdir()?;
+        let web = test_dir.path().join("web");
+        let libs = test_dir.path().join("libs");
+        for repo in [&web, &libs] {
+            std::fs::create_dir_all(repo.join(".git"))?;
+        }
+        std::fs::create_dir_all(libs.join("ds"))?;
+        std::fs::create_dir_all(libs.join("auth"))?;
+
+        std::fs::write(
+            web.join("package.json"),
+            r#"{"name": "web", "dependencies": {"@bxbdigital/design-system": "^2.0.0"}}"#,
+        )?;
+        std::fs::write(
+            web.join("composer.json"),
+            r#"{"name": "bxbdigital/web", "require": {"bxbdigital/auth-bundle": "^1.0"}}"#,
+        )?;
+        std::fs::write(
+            libs.join("ds").join("package.json"),
+            r#"{"name": "@bxbdigital/design-system", "version": "2.1.0"}"#,
+        )?;
+        std::fs::write(
+            libs.join("auth").join("composer.json"),
+            r#"{"name": "bxbdigital/auth-bundle"}"#,
+        )?;
+
+        let (repos, failures) = scan_all(test_dir.path(), 2).await;
+        assert!(failures.is_empty());
+        let index = build_index(&repos)?;
+
+        let web_repo = &repos[1];
+        let self_ref = web_repo.repo.projects[0].get_entity_ref(&web, "web");
+        let entity_refs: Vec<String> = web_repo.repo.projects[0]
+            .get_internal_dependencies(&self_ref, &web_repo.config, &index)?
+            .into_iter()
+            .map(|dependency| dependency.entity_ref)
+            .collect();
+
+        assert_eq!(
+            entity_refs,
+            vec!["component:default/ds", "component:default/auth"]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+use crate::project::GetProjectDependenciesError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FindSkewError {
+    #[error("Failed to get a project's internal dependencies: {0}")]
+    GetInternalDependencies(#[from] GetProjectDependenciesError),
+}
@@ -0,0 +1,129 @@
+mod errors;
+
+use crate::index::DependencyIndex;
+use crate::scan::ScannedRepo;
+pub use errors::FindSkewError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// An internal component that projects require at different versions.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct VersionSkew {
+    /// The entity ref of the component, e.g. `component:default/ledger`
+    #[serde(rename = "entityRef")]
+    pub entity_ref: String,
+
+    /// Maps each version required to the entity refs of the projects requiring it
+    pub versions: BTreeMap<String, Vec<String>>,
+
+    /// The projects depending on the component without requiring a version
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unversioned: Vec<String>,
+}
+
+/// Finds the internal components that the scanned projects require at `min_versions` or more
+/// different versions, most skewed first. Projects depending on a component only through other
+/// dependencies count the same as those depending on it directly.
+pub fn find_skew(
+    repos: &[ScannedRepo],
+    index: &DependencyIndex,
+    min_versions: usize,
+) -> Result<Vec<VersionSkew>, FindSkewError> {
+    let mut skews: BTreeMap<String, VersionSkew> = BTreeMap::new();
+
+    for scanned in repos {
+        let repo = &scanned.repo;
+        for project in &repo.projects {
+            let self_ref = project.get_entity_ref(&repo.root, &scanned.name);
+            for dependency in
+                project.get_internal_dependencies(&self_ref, &scanned.config, index)?
+            {
+                let skew = skews
+                    .entry(dependency.entity_ref.clone())
+                    .or_insert_with(|| VersionSkew {
+                        entity_ref: dependency.entity_ref.clone(),
+                        versions: BTreeMap::new(),
+                        unversioned: vec![],
+                    });
+                if dependency.versions.is_empty() {
+                    skew.unversioned.push(self_ref.clone());
+                }
+                for version in dependency.versions {
+                    skew.versions
+                        .entry(version)
+                        .or_default()
+                        .push(self_ref.clone());
+                }
+            }
+        }
+    }
+
+    let mut skews: Vec<VersionSkew> = skews
+        .into_values()
+        .filter(|skew| skew.versions.len() >= min_versions)
+        .collect();
+    skews.sort_by_key(|skew| std::cmp::Reverse(skew.versions.len()));
+
+    Ok(skews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_repo;
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_find_skew() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::create_dir(test_dir.path().join(".git"))?;
+
+        for (module, requires) in [
+            ("ledger", "\tbitbucket.org/bxbdigital/money v0.3.0\n"),
+            (
+                "payments",
+                "\tbitbucket.org/bxbdigital/ledger v1.4.0\n\tbitbucket.org/bxbdigital/money v0.3.0\n",
+            ),
+            ("checkout", "\tbitbucket.org/bxbdigital/ledger v1.2.1\n"),
+            (
+                "refunds",
+                "\tbitbucket.org/bxbdigital/ledger v1.2.1 // indirect\n",
+            ),
+        ] {
+            std::fs::create_dir(test_dir.path().join(module))?;
+            std::fs::write(
+                test_dir.path().join(module).join("go.mod"),
+                format!("module bitbucket.org/bxbdigital/{module}\n\nrequire (\n{requires})\n"),
+            )?;
+        }
+
+        let scanned = scan_repo(Some(test_dir.path().into())).await?;
+        let mut index = DependencyIndex::default();
+        index.add_repo(&scanned.repo, &scanned.name)?;
+
+        let skews = find_skew(&[scanned], &index, 2)?;
+        assert_eq!(
+            skews,
+            vec![VersionSkew {
+                entity_ref: "component:default/ledger".to_string(),
+                versions: BTreeMap::from([
+                    (
+                        "v1.2.1".to_string(),
+                        vec![
+                            "component:default/checkout".to_string(),
+                            "component:default/refunds".to_string()
+                        ]
+                    ),
+                    (
+                        "v1.4.0".to_string(),
+                        vec!["component:default/payments".to_string()]
+                    ),
+                ]),
+                unversioned: vec![],
+            }]
+        );
+
+        Ok(())
+    }
+}
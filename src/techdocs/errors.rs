@@ -0,0 +1,20 @@
+use crate::readme::ReadReadmeError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScaffoldTechDocsError {
+    #[error("Failed to read the project's README: {0}")]
+    ReadReadme(#[from] ReadReadmeError),
+
+    #[error("Failed to create the docs directory {path}: {source}")]
+    FailedToCreateDocsDirectory {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to write the TechDocs file {path}: {source}")]
+    FailedToWriteTechDocsFile {
+        path: String,
+        source: std::io::Error,
+    },
+}
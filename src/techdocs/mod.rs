@@ -0,0 +1,159 @@
+mod errors;
+
+use crate::readme::{ReadReadmeError, Readme, ReadmeFormat, find_readme};
+pub use errors::ScaffoldTechDocsError;
+use std::path::{Path, PathBuf};
+
+/// Where a project's mkdocs config can live, relative to the project root.
+const MKDOCS_FILES: [&str; 4] = [
+    "mkdocs.yml",
+    "mkdocs.yaml",
+    "docs/mkdocs.yml",
+    "docs/mkdocs.yaml",
+];
+
+/// Finds the mkdocs config of the project, if it has one.
+pub fn find_mkdocs_config(project_root: &Path) -> Option<PathBuf> {
+    MKDOCS_FILES
+        .iter()
+        .map(|file| project_root.join(file))
+        .find(|path| path.is_file())
+}
+
+/// The `backstage.io/techdocs-ref` of the project, pointing at the directory of its mkdocs
+/// config relative to `catalog_dir`, the directory of the catalog info file it is written to.
+pub fn techdocs_ref(project_root: &Path, catalog_dir: &Path) -> Option<String> {
+    let mkdocs_dir = find_mkdocs_config(project_root)?.parent()?.to_path_buf();
+    let relative = mkdocs_dir.strip_prefix(catalog_dir).unwrap_or(&mkdocs_dir);
+
+    if relative.as_os_str().is_empty() {
+        Some("dir:.".to_string())
+    } else {
+        Some(format!("dir:./{}", relative.display()))
+    }
+}
+
+/// Writes a minimal mkdocs config and `docs/index.md` for a project without an mkdocs config.
+/// The index is a copy of a Markdown README, or the README's description otherwise. Returns
+/// whether anything was scaffolded.
+pub fn scaffold(project_root: &Path, site_name: &str) -> Result<bool, ScaffoldTechDocsError> {
+    if find_mkdocs_config(project_root).is_some() {
+        return Ok(false);
+    }
+
+    let docs_dir = project_root.join("docs");
+    std::fs::create_dir_all(&docs_dir).map_err(|source| {
+        ScaffoldTechDocsError::FailedToCreateDocsDirectory {
+            path: format!("{}", docs_dir.display()),
+            source,
+        }
+    })?;
+
+    write_file(
+        &project_root.join("mkdocs.yml"),
+        &format!("site_name: {site_name}\nnav:\n  - Home: index.md\nplugins:\n  - techdocs-core\n"),
+    )?;
+
+    // existing docs are kept as they are
+    let index_path = docs_dir.join("index.md");
+    if !index_path.exists() {
+        let index_text = match find_readme(project_root) {
+            Some((readme_path, ReadmeFormat::Markdown)) => std::fs::read_to_string(&readme_path)
+                .map_err(|source| ReadReadmeError::FailedToReadReadme {
+                    path: format!("{}", readme_path.display()),
+                    source,
+                })?,
+            _ => {
+                let description = Readme::read(project_root)?
+                    .and_then(|readme| readme.description)
+                    .unwrap_or_default();
+                format!("# {site_name}\n\n{description}\n")
+            }
+        };
+        write_file(&index_path, &index_text)?;
+    }
+
+    Ok(true)
+}
+
+fn write_file(path: &Path, text: &str) -> Result<(), ScaffoldTechDocsError> {
+    std::fs::write(path, text).map_err(|source| ScaffoldTechDocsError::FailedToWriteTechDocsFile {
+        path: format!("{}", path.display()),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{LayoutConfig, LayoutMode};
+    use anyhow::{Ok, Result};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_techdocs_ref() -> Result<()> {
+        let test_dir = tempdir()?;
+        assert_eq!(techdocs_ref(test_dir.path(), test_dir.path()), None);
+
+        std::fs::create_dir(test_dir.path().join("docs"))?;
+        std::fs::write(test_dir.path().join("docs").join("mkdocs.yml"), "")?;
+        assert_eq!(
+            techdocs_ref(test_dir.path(), test_dir.path()),
+            Some("dir:./docs".to_string())
+        );
+
+        std::fs::write(test_dir.path().join("mkdocs.yml"), "")?;
+        assert_eq!(
+            techdocs_ref(test_dir.path(), test_dir.path()),
+            Some("dir:.".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_techdocs_ref_single_layout() -> Result<()> {
+        let test_dir = tempdir()?;
+        let project_root = test_dir.path().join("services").join("payments");
+        std::fs::create_dir_all(project_root.join("docs"))?;
+        std::fs::write(project_root.join("docs").join("mkdocs.yml"), "")?;
+
+        // every entity is written to the catalog info file in the repository root
+        let catalog_dir = LayoutConfig {
+            mode: LayoutMode::Single,
+            ..LayoutConfig::default()
+        }
+        .catalog_dir(test_dir.path(), &project_root);
+
+        assert_eq!(
+            techdocs_ref(&project_root, &catalog_dir),
+            Some("dir:./services/payments/docs".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scaffold() -> Result<()> {
+        let test_dir = tempdir()?;
+        std::fs::write(
+            test_dir.path().join("README.rst"),
+            "Payments\n========\n\nTakes card payments.\n",
+        )?;
+
+        assert!(scaffold(test_dir.path(), "payments")?);
+        assert_eq!(
+            std::fs::read_to_string(test_dir.path().join("docs").join("index.md"))?,
+            "# payments\n\nTakes card payments.\n"
+        );
+        assert_eq!(
+            techdocs_ref(test_dir.path(), test_dir.path()),
+            Some("dir:.".to_string())
+        );
+
+        // a project that already has docs is left alone
+        assert!(!scaffold(test_dir.path(), "payments")?);
+
+        Ok(())
+    }
+}